@@ -0,0 +1,195 @@
+use actix_web::http::uri::{InvalidUri, Uri};
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use handlebars::Handlebars;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, PercentEncode, NON_ALPHANUMERIC};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+
+use crate::config::SiteConfig;
+use crate::models::{PostStore, Taxonomy};
+use crate::routes;
+
+// Everything but the unreserved characters is escaped in a path segment
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+// Routes that serve a file of their own rather than a page
+const FILE_NAMES: [&str; 5] = ["feed.xml", "atom.xml", "feed.json", "sitemap.xml", "robots.txt"];
+
+#[derive(Error, Debug)]
+pub enum BuildError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("{path} responded with {status}")]
+    Status { path: String, status: StatusCode },
+
+    #[error("Cannot request {path}: {source}")]
+    InvalidPath { path: String, source: InvalidUri },
+}
+
+/// Renders every page of the site into `out_dir` as static files.
+///
+/// Each page is requested from the same routes the server uses, so the files
+/// are byte-for-byte what the server would have sent. Pages are written with
+/// pretty URLs (`/post/hello` becomes `post/hello/index.html`) and the static
+/// directory is copied to `static/`. Returns the number of pages written.
+pub async fn build(
+    config: Arc<SiteConfig>,
+    handlebars: Arc<RwLock<Handlebars<'static>>>,
+    store: Arc<RwLock<PostStore>>,
+    out_dir: &Path,
+) -> Result<usize, BuildError> {
+    let (paths, bundles) = {
+        let store = store.read().expect("post store lock poisoned");
+        let mut bundles: Vec<_> = store
+            .posts(config.visibility())
+            .iter()
+            .filter_map(|post| Some((Path::new("post").join(&post.slug), post.bundle_dir()?.to_path_buf())))
+            .collect();
+        bundles.extend(
+            store
+                .pages()
+                .iter()
+                .filter_map(|page| Some((PathBuf::from(&page.slug), page.bundle_dir()?.to_path_buf()))),
+        );
+        (site_paths(&config, &store), bundles)
+    };
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(handlebars))
+            .app_data(web::Data::new(store))
+            .app_data(web::Data::new(config.clone()))
+            .configure(routes::configure),
+    )
+    .await;
+
+    let mut written = 0;
+    for path in paths {
+        let resp = test::call_service(&app, request(&path)?.to_request()).await;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(BuildError::Status { path, status });
+        }
+        write_page(out_dir, &path, &test::read_body(resp).await)?;
+        written += 1;
+    }
+
+    // Later pages of the home page and archive, until we run out
+    for base in ["/page", "/archive/page"] {
+        for n in 2.. {
+            let path = format!("{}/{}", base, n);
+            let resp = test::call_service(&app, request(&path)?.to_request()).await;
+            let status = resp.status();
+            if status == StatusCode::NOT_FOUND {
+                break;
+            }
+            if !status.is_success() {
+                return Err(BuildError::Status { path, status });
+            }
+            write_page(out_dir, &path, &test::read_body(resp).await)?;
+            written += 1;
+        }
+    }
+
+    // Page bundle assets go next to the post's or page's index.html
+    for (target, dir) in bundles {
+        copy_dir(&dir, &out_dir.join(target), false)?;
+    }
+
+    let static_dir = config.static_path();
+    if static_dir.is_dir() {
+        copy_dir(&static_dir, &out_dir.join("static"), true)?;
+    }
+
+    Ok(written)
+}
+
+// A GET request for `path`, which must already be percent-encoded
+fn request(path: &str) -> Result<test::TestRequest, BuildError> {
+    path.parse::<Uri>().map_err(|source| BuildError::InvalidPath { path: path.to_string(), source })?;
+    Ok(test::TestRequest::get().uri(path))
+}
+
+fn encode(segment: &str) -> PercentEncode<'_> {
+    utf8_percent_encode(segment, SEGMENT)
+}
+
+// Every route with a single, known set of parameters, percent-encoded
+fn site_paths(config: &SiteConfig, store: &PostStore) -> Vec<String> {
+    let visibility = config.visibility();
+    let mut paths: Vec<String> = [
+        "/", "/archive", "/feed.xml", "/atom.xml", "/feed.json", "/sitemap.xml", "/robots.txt",
+    ]
+    .iter()
+    .map(|path| path.to_string())
+    .collect();
+
+    paths.extend(store.posts(visibility).iter().map(|post| format!("/post/{}", encode(&post.slug))));
+    paths.extend(store.pages().iter().map(|page| format!("/{}", encode(&page.slug))));
+    paths.extend(config.authors.iter().map(|author| format!("/authors/{}", encode(&author.id))));
+    paths.extend(store.all_series(visibility).iter().map(|series| format!("/series/{}", encode(&series.slug))));
+
+    for taxonomy in [Taxonomy::Tags, Taxonomy::Categories] {
+        paths.push(format!("/{}", taxonomy.name()));
+        for term in store.terms(taxonomy, visibility) {
+            let base = format!("/{}/{}", taxonomy.name(), encode(&term.slug));
+            for feed in ["feed.xml", "atom.xml", "feed.json"] {
+                paths.push(format!("{}/{}", base, feed));
+            }
+            paths.push(base);
+        }
+    }
+
+    paths
+}
+
+/// Where the page at the percent-encoded `path` is written: feeds, the
+/// sitemap and `robots.txt` keep their name, everything else becomes
+/// `index.html` in a directory of its own, even when its slug has a dot.
+pub fn output_path(out_dir: &Path, path: &str) -> PathBuf {
+    let relative = percent_decode_str(path.trim_matches('/')).decode_utf8_lossy();
+    // Site-wide files, or a term's feeds under `/tags/{slug}/`
+    let is_file = match relative.split('/').collect::<Vec<_>>()[..] {
+        [name] | [_, _, name] => FILE_NAMES.contains(&name),
+        _ => false,
+    };
+    if is_file {
+        out_dir.join(&*relative)
+    } else {
+        out_dir.join(&*relative).join("index.html")
+    }
+}
+
+fn write_page(out_dir: &Path, path: &str, body: &[u8]) -> io::Result<()> {
+    let file = output_path(out_dir, path);
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(file, body)
+}
+
+// Copies a directory tree without hidden files, the way the server leaves
+// them out, and optionally without Markdown sources
+fn copy_dir(from: &Path, to: &Path, markdown: bool) -> io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if !markdown && entry.path().extension().is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case("md")) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target, markdown)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::SiteConfig;
+use crate::models::{
+    markdown_files, post_links, series_warnings, unknown_languages, BlogPost, BlogPostError, Page, PAGES_DIR,
+};
+
+/// Something wrong with a post, reported by `andy check`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub path: PathBuf,
+    /// 1-based line in the file, when known
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path.display(), line, self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+/// Parses every post and page in `content_dir` with `config` and reports
+/// everything wrong with them, in file order. Unlike loading, nothing is
+/// skipped.
+pub async fn check(content_dir: &Path, config: &SiteConfig) -> Result<Vec<Problem>, BlogPostError> {
+    let mut problems = Vec::new();
+    let mut posts = Vec::new();
+    let mut pages = Vec::new();
+
+    let pages_dir = content_dir.join(PAGES_DIR);
+    for path in markdown_files(content_dir, Some(&pages_dir)).await? {
+        match BlogPost::from_file(&path, content_dir, config).await {
+            Ok(post) => posts.push(post),
+            Err(err) => problems.push(parse_problem(path, err)),
+        }
+    }
+    if pages_dir.is_dir() {
+        for path in markdown_files(&pages_dir, None).await? {
+            match Page::from_file(&path, &pages_dir, config).await {
+                Ok(page) => pages.push(page),
+                Err(err) => problems.push(parse_problem(path, err)),
+            }
+        }
+    }
+
+    let mut by_slug: HashMap<&str, &BlogPost> = HashMap::new();
+    for post in &posts {
+        if let Some(first) = by_slug.insert(&post.slug, post) {
+            problems.push(Problem {
+                path: post.source.clone(),
+                line: None,
+                message: format!("Duplicate slug {:?}, also used by {}", post.slug, first.source.display()),
+            });
+        }
+    }
+
+    for warning in series_warnings(&posts) {
+        problems.push(Problem { path: warning.source, line: None, message: warning.message });
+    }
+
+    let bodies = posts
+        .iter()
+        .map(|post| (&post.source, &post.content))
+        .chain(pages.iter().map(|page| (&page.source, &page.content)));
+    for (source, content) in bodies {
+        // Offsets are into the body, which is the tail of the file
+        let raw = tokio::fs::read_to_string(source).await?;
+        let body_start = raw.len() - content.len();
+        let line = |offset: usize| Some(raw[..body_start + offset].matches('\n').count() + 1);
+
+        for (offset, lang) in unknown_languages(content) {
+            problems.push(Problem {
+                path: source.clone(),
+                line: line(offset),
+                message: format!("Unknown code block language {:?}", lang),
+            });
+        }
+        for (offset, slug) in post_links(content) {
+            if !by_slug.contains_key(slug.as_str()) {
+                problems.push(Problem {
+                    path: source.clone(),
+                    line: line(offset),
+                    message: format!("Broken link to /post/{}", slug),
+                });
+            }
+        }
+    }
+
+    problems.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    Ok(problems)
+}
+
+fn parse_problem(path: PathBuf, err: BlogPostError) -> Problem {
+    let line = match &err {
+        BlogPostError::FrontmatterParse { line, .. } => Some(*line),
+        _ => None,
+    };
+    Problem { path, line, message: err.to_string() }
+}
@@ -0,0 +1,353 @@
+use hmac::{Hmac, Mac};
+use pulldown_cmark::Options;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::models::{Author, Visibility};
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse config file {path:?}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// Markdown extensions beyond CommonMark, all on by default.
+///
+/// Set per site in the `[markdown]` table of `config.toml` and per post
+/// with a `markdown` map in frontmatter.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarkdownOptions {
+    pub tables: bool,
+    pub footnotes: bool,
+    pub strikethrough: bool,
+    pub tasklists: bool,
+    /// Curly quotes, en and em dashes and ellipses.
+    pub smart_punctuation: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            tables: true,
+            footnotes: true,
+            strikethrough: true,
+            tasklists: true,
+            smart_punctuation: true,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// The matching pulldown-cmark parser options.
+    pub fn parser_options(&self) -> Options {
+        let mut options = Options::empty();
+        options.set(Options::ENABLE_TABLES, self.tables);
+        options.set(Options::ENABLE_FOOTNOTES, self.footnotes);
+        options.set(Options::ENABLE_STRIKETHROUGH, self.strikethrough);
+        options.set(Options::ENABLE_TASKLISTS, self.tasklists);
+        options.set(Options::ENABLE_SMART_PUNCTUATION, self.smart_punctuation);
+        options
+    }
+}
+
+/// A link in the site navigation.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct MenuItem {
+    pub title: String,
+    pub url: String,
+    /// Position in the navigation, lowest first.
+    #[serde(default)]
+    pub order: i64,
+}
+
+impl MenuItem {
+    fn new(title: &str, url: &str, order: i64) -> Self {
+        Self { title: title.to_string(), url: url.to_string(), order }
+    }
+}
+
+/// Site-wide settings shared by all handlers.
+///
+/// Loaded from `config.toml`, with `ANDY_*` environment variables taking
+/// precedence. Templates see it as the global `site` object.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SiteConfig {
+    /// Address the server listens on.
+    pub bind: String,
+    /// Public address of the site, used to build absolute URLs in feeds.
+    pub base_url: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    /// Directory holding the Markdown posts.
+    pub content_dir: PathBuf,
+    /// Directory holding the Handlebars templates.
+    pub templates_dir: PathBuf,
+    /// Directory served under `/static`.
+    pub static_dir: PathBuf,
+    /// TOML file describing the people posts can name as `author`.
+    pub authors_file: PathBuf,
+    /// The authors from `authors_file`, sorted by id.
+    #[serde(skip_deserializing)]
+    pub authors: Vec<Author>,
+    /// Name of a theme in `themes/`. Its `templates` and `static`
+    /// directories are used instead of `templates_dir` and `static_dir`.
+    pub theme: Option<String>,
+    /// Refuse to start when two posts share a slug, instead of serving the
+    /// newer one under a suffixed slug.
+    pub strict_slugs: bool,
+    /// Use a post's file modification time as its `updated` date when the
+    /// frontmatter doesn't give one.
+    pub updated_from_mtime: bool,
+    /// Markdown extensions used for every post unless it overrides them.
+    pub markdown: MarkdownOptions,
+    /// Navigation links besides the pages that ask to be listed.
+    pub menu: Vec<MenuItem>,
+    /// Number of posts per page on the home page.
+    pub home_page_size: usize,
+    /// Number of posts per page in the archive.
+    pub archive_page_size: usize,
+    /// Put each post's full HTML in feeds rather than just its description.
+    pub feed_full_content: bool,
+    /// Paths crawlers are asked to stay out of in `/robots.txt`.
+    pub robots_disallow: Vec<String>,
+    /// Show drafts and scheduled posts everywhere, as if they were published.
+    pub preview: bool,
+    /// Secret used to sign per-post preview links. Without it, drafts and
+    /// scheduled posts can only be seen with `preview` turned on.
+    #[serde(skip_serializing)]
+    pub preview_secret: Option<String>,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:8080".to_string(),
+            base_url: "http://localhost:8080".to_string(),
+            title: "Blog".to_string(),
+            description: "Personal blog and website".to_string(),
+            author: "Andy".to_string(),
+            content_dir: PathBuf::from("content"),
+            templates_dir: PathBuf::from("templates"),
+            static_dir: PathBuf::from("static"),
+            authors_file: PathBuf::from("authors.toml"),
+            authors: Vec::new(),
+            theme: None,
+            strict_slugs: false,
+            updated_from_mtime: false,
+            markdown: MarkdownOptions::default(),
+            menu: vec![
+                MenuItem::new("Latest", "/", 10),
+                MenuItem::new("Archive", "/archive", 20),
+                MenuItem::new("Tags", "/tags", 30),
+            ],
+            home_page_size: 5,
+            archive_page_size: 50,
+            feed_full_content: true,
+            robots_disallow: vec!["/api/".to_string()],
+            preview: false,
+            preview_secret: None,
+        }
+    }
+}
+
+impl SiteConfig {
+    /// Reads `path`, then applies environment overrides and loads the
+    /// authors file.
+    ///
+    /// Missing files are not an error; the defaults are used instead.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let mut config = match std::fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|source| ConfigError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                log::info!("No config file at {:?}, using defaults", path);
+                Self::default()
+            }
+            Err(source) => {
+                return Err(ConfigError::Io { path: path.to_path_buf(), source });
+            }
+        };
+        config.apply_env();
+        config.authors = load_authors(&config.authors_file)?;
+        Ok(config)
+    }
+
+    // Overrides settings with any `ANDY_*` environment variables that are set
+    fn apply_env(&mut self) {
+        override_with(&mut self.bind, "ANDY_BIND");
+        override_with(&mut self.base_url, "ANDY_BASE_URL");
+        override_with(&mut self.title, "ANDY_SITE_TITLE");
+        override_with(&mut self.description, "ANDY_SITE_DESCRIPTION");
+        override_with(&mut self.author, "ANDY_AUTHOR");
+        override_with(&mut self.content_dir, "ANDY_CONTENT_DIR");
+        override_with(&mut self.templates_dir, "ANDY_TEMPLATES_DIR");
+        override_with(&mut self.static_dir, "ANDY_STATIC_DIR");
+        override_with(&mut self.authors_file, "ANDY_AUTHORS_FILE");
+        if let Some(theme) = env_var("ANDY_THEME") {
+            self.theme = Some(theme);
+        }
+        if let Some(value) = env_var("ANDY_STRICT_SLUGS") {
+            self.strict_slugs = is_truthy(&value);
+        }
+        if let Some(value) = env_var("ANDY_UPDATED_FROM_MTIME") {
+            self.updated_from_mtime = is_truthy(&value);
+        }
+        override_with(&mut self.home_page_size, "ANDY_HOME_PAGE_SIZE");
+        override_with(&mut self.archive_page_size, "ANDY_ARCHIVE_PAGE_SIZE");
+        if let Some(value) = env_var("ANDY_FEED_FULL_CONTENT") {
+            self.feed_full_content = is_truthy(&value);
+        }
+        if let Some(value) = env_var("ANDY_ROBOTS_DISALLOW") {
+            self.robots_disallow = split_list(&value);
+        }
+        if let Some(value) = env_var("ANDY_PREVIEW") {
+            self.preview = is_truthy(&value);
+        }
+        if let Some(secret) = env_var("ANDY_PREVIEW_SECRET") {
+            self.preview_secret = Some(secret);
+        }
+    }
+
+    /// The templates directory, taking the theme into account.
+    pub fn templates_path(&self) -> PathBuf {
+        match &self.theme {
+            Some(theme) => Path::new("themes").join(theme).join("templates"),
+            None => self.templates_dir.clone(),
+        }
+    }
+
+    /// The static files directory, taking the theme into account.
+    pub fn static_path(&self) -> PathBuf {
+        match &self.theme {
+            Some(theme) => Path::new("themes").join(theme).join("static"),
+            None => self.static_dir.clone(),
+        }
+    }
+
+    /// Looks up an author by id.
+    pub fn author(&self, id: &str) -> Option<&Author> {
+        self.authors.iter().find(|author| author.id == id)
+    }
+
+    /// Turns a site-relative path like `/post/hello` into an absolute URL.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), path.trim_start_matches('/'))
+    }
+
+    /// Which posts listing pages should show.
+    pub fn visibility(&self) -> Visibility {
+        if self.preview {
+            Visibility::Preview
+        } else {
+            Visibility::Published
+        }
+    }
+
+    /// The token that unlocks a preview of the post with `slug`, as passed in
+    /// `/post/{slug}?preview=<token>`.
+    pub fn preview_token(&self, slug: &str) -> Option<String> {
+        let mac = self.preview_mac(slug)?;
+        let signature = mac.finalize().into_bytes();
+        Some(signature.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Checks a preview token for the post with `slug`.
+    pub fn verify_preview_token(&self, slug: &str, token: &str) -> bool {
+        let (Some(mac), Some(signature)) = (self.preview_mac(slug), decode_hex(token)) else {
+            return false;
+        };
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    fn preview_mac(&self, slug: &str) -> Option<HmacSha256> {
+        let secret = self.preview_secret.as_ref()?;
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(slug.as_bytes());
+        Some(mac)
+    }
+}
+
+// One table per author, keyed by id. Without a file there are no authors.
+fn load_authors(path: &Path) -> Result<Vec<Author>, ConfigError> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => return Err(ConfigError::Io { path: path.to_path_buf(), source }),
+    };
+    let authors: BTreeMap<String, Author> = toml::from_str(&text).map_err(|source| ConfigError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    Ok(authors
+        .into_iter()
+        .map(|(id, author)| Author { id, ..author })
+        .collect())
+}
+
+// Unset and empty variables are treated the same
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+// Parses the variable into `target` if set, keeping the old value on errors
+fn override_with<T>(target: &mut T, name: &str)
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let Some(value) = env_var(name) else {
+        return;
+    };
+    match value.parse() {
+        Ok(value) => *target = value,
+        Err(err) => log::warn!("Ignoring {}={:?}: {}", name, value, err),
+    }
+}
+
+// Comma separated list, ignoring blanks
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,62 @@
+//! Handlebars helpers available to every template.
+
+use chrono::{DateTime, Utc};
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext};
+
+use crate::slug::slugify;
+
+/// Registers all of the site's helpers with `handlebars`.
+pub fn register(handlebars: &mut Handlebars) {
+    handlebars.register_helper("formatDate", Box::new(format_date_helper));
+    handlebars.register_helper("currentYear", Box::new(current_year_helper));
+    handlebars.register_helper("slugify", Box::new(slugify_helper));
+}
+
+// Formats an RFC3339 date for display, e.g. March 1, 2024
+fn format_date_helper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    // Get the first parameter as a string
+    let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
+    
+    // Parse the date string
+    if let Ok(date) = DateTime::parse_from_rfc3339(param) {
+        let utc_date = date.with_timezone(&Utc);
+        // Format date: March 1, 2024
+        let formatted = utc_date.format("%B %e, %Y").to_string();
+        out.write(&formatted)?;
+    } else {
+        out.write(param)?;
+    }
+    
+    Ok(())
+}
+
+fn current_year_helper(
+    _: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let current_year = Utc::now().format("%Y").to_string();
+    out.write(&current_year)?;
+    Ok(())
+}
+
+// Turns a tag or category name into the slug used in its URL
+fn slugify_helper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
+    out.write(&slugify(param))?;
+    Ok(())
+}
@@ -1,2 +1,9 @@
 pub mod routes;
-pub mod models;
\ No newline at end of file
+pub mod models;
+pub mod config;
+pub mod helpers;
+pub mod reload;
+pub mod slug;
+pub mod build;
+pub mod scaffold;
+pub mod check;
@@ -1,76 +1,202 @@
 use actix_web::{web, App, HttpServer, middleware};
 use actix_files as fs;
+use clap::{Parser, Subcommand};
 use handlebars::Handlebars;
-use std::sync::Arc;
-use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 
-use andy::routes;
+use andy::config::SiteConfig;
+use andy::models::{PostStatus, PostStore};
+use andy::{build, check, helpers, reload, routes, scaffold};
 
-// Helper function for formatting dates in Handlebars templates
-fn format_date_helper(
-    h: &handlebars::Helper,
-    _: &handlebars::Handlebars,
-    _: &handlebars::Context,
-    _: &mut handlebars::RenderContext,
-    out: &mut dyn handlebars::Output,
-) -> handlebars::HelperResult {
-    // Get the first parameter as a string
-    let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Site configuration file
+    #[arg(long, global = true, env = "ANDY_CONFIG", default_value = "config.toml")]
+    config: PathBuf,
     
-    // Parse the date string
-    if let Ok(date) = DateTime::parse_from_rfc3339(param) {
-        let utc_date = date.with_timezone(&Utc);
-        // Format date: March 1, 2024
-        let formatted = utc_date.format("%B %e, %Y").to_string();
-        out.write(&formatted)?;
-    } else {
-        out.write(param)?;
-    }
+    /// Reload posts and templates as they change on disk
+    #[arg(long, global = true, env = "ANDY_DEV")]
+    dev: bool,
     
-    Ok(())
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve the site (the default)
+    Serve,
+    /// Render the whole site to static files
+    Build {
+        /// Directory to write the site to
+        #[arg(short, long, default_value = "public")]
+        output: PathBuf,
+    },
+    /// Create a new post in the content directory
+    New {
+        /// Title of the post; the slug and file name are made from it
+        title: String,
+    },
+    /// Report problems with posts and exit non-zero if there are any
+    Check,
+    /// Print signed preview links for drafts and scheduled posts
+    PreviewLinks,
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
-    log::info!("Starting server at http://localhost:8080");
-
+    
+    let cli = Cli::parse();
+    let config = SiteConfig::load(&cli.config).expect("Failed to load site configuration");
+    if config.preview {
+        log::warn!("Preview mode is on: drafts and scheduled posts are visible");
+    }
+    
+    // Scaffolding and checking don't need the rendered site
+    match &cli.command {
+        Some(Command::New { title }) => {
+            match scaffold::new_post(&config, title, chrono::Utc::now()).await {
+                Ok(path) => {
+                    log::info!("Created {:?}", path);
+                    return Ok(());
+                }
+                Err(err) => {
+                    log::error!("Cannot create post: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Check) => {
+            let problems = check::check(&config.content_dir, &config).await.unwrap_or_else(|err| {
+                log::error!("Cannot check posts: {}", err);
+                std::process::exit(1);
+            });
+            for problem in &problems {
+                println!("{}", problem);
+            }
+            if !problems.is_empty() {
+                eprintln!("Found {} problem(s)", problems.len());
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Command::PreviewLinks) => {
+            if config.preview_secret.is_none() {
+                log::error!("Set preview_secret to sign preview links");
+                std::process::exit(1);
+            }
+            let post_store = PostStore::load(&config.content_dir, &config).await.unwrap_or_else(|err| {
+                log::error!("Cannot load posts: {}", err);
+                std::process::exit(1);
+            });
+            let now = chrono::Utc::now();
+            for post in post_store.all() {
+                let status = post.status(now);
+                if status == PostStatus::Published {
+                    continue;
+                }
+                if let Some(token) = config.preview_token(&post.slug) {
+                    println!("{:?}: /post/{}?preview={}", status, post.slug, token);
+                }
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+    
     // Initialize handlebars
     let mut handlebars = Handlebars::new();
     
-    // Register formatDate helper
-    handlebars.register_helper("formatDate", Box::new(format_date_helper));
-    
-    // Add currentYear helper
-    handlebars.register_helper("currentYear", Box::new(|
-        _: &handlebars::Helper,
-        _: &handlebars::Handlebars,
-        _: &handlebars::Context,
-        _: &mut handlebars::RenderContext,
-        out: &mut dyn handlebars::Output,
-    | -> handlebars::HelperResult {
-        let current_year = Utc::now().format("%Y").to_string();
-        out.write(&current_year)?;
-        Ok(())
-    }));
+    // Register formatDate, currentYear and friends
+    helpers::register(&mut handlebars);
     
     // Register all templates
     handlebars
-        .register_templates_directory(".hbs", "templates")
+        .register_templates_directory(".hbs", config.templates_path())
         .expect("Failed to register handlebars templates");
-    let handlebars_ref = Arc::new(handlebars);
+    let handlebars_ref = Arc::new(RwLock::new(handlebars));
+
+    // Load and render all posts once up front
+    let post_store = PostStore::load(&config.content_dir, &config)
+        .await
+        .expect("Failed to load blog posts");
+    log::info!("Loaded {} blog posts", post_store.len());
+    
+    if config.strict_slugs && !post_store.duplicates().is_empty() {
+        for duplicate in post_store.duplicates() {
+            log::error!(
+                "Duplicate slug {:?} in {:?} and {:?}",
+                duplicate.slug, duplicate.kept, duplicate.renamed
+            );
+        }
+        log::error!("Refusing to start with duplicate slugs (strict_slugs is on)");
+        std::process::exit(1);
+    }
+    
+    let post_store_ref = Arc::new(RwLock::new(post_store));
+    let config_ref = Arc::new(config);
+    
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(config_ref, handlebars_ref, post_store_ref, cli.dev).await,
+        Command::Build { output } => {
+            match build::build(config_ref, handlebars_ref, post_store_ref, &output).await {
+                Ok(pages) => {
+                    log::info!("Wrote {} pages to {:?}", pages, output);
+                    Ok(())
+                }
+                Err(err) => {
+                    log::error!("Build failed: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::New { .. } | Command::Check | Command::PreviewLinks => {
+            unreachable!("handled before loading the site")
+        }
+    }
+}
+
+async fn serve(
+    config_ref: Arc<SiteConfig>,
+    handlebars_ref: Arc<RwLock<Handlebars<'static>>>,
+    post_store_ref: Arc<RwLock<PostStore>>,
+    dev_mode: bool,
+) -> std::io::Result<()> {
+    log::info!("Starting server at http://{}", config_ref.bind);
+    
+    // Keep the watcher alive for as long as the server runs
+    let _watcher = if dev_mode {
+        let watcher = reload::watch(
+            &config_ref.content_dir,
+            config_ref.templates_path(),
+            post_store_ref.clone(),
+            handlebars_ref.clone(),
+            config_ref.clone(),
+        )
+        .expect("Failed to start file watcher");
+        Some(watcher)
+    } else {
+        None
+    };
 
     // Start http server
+    let bind = config_ref.bind.clone();
+    let static_dir = config_ref.static_path();
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(handlebars_ref.clone()))
+            .app_data(web::Data::new(post_store_ref.clone()))
+            .app_data(web::Data::new(config_ref.clone()))
             .wrap(middleware::Logger::default())
             // Static files
-            .service(fs::Files::new("/static", "static"))
+            .service(fs::Files::new("/static", &static_dir))
             // Routes
             .configure(routes::configure)
     })
-    .bind("127.0.0.1:8080")?
+    .bind(bind)?
     .run()
     .await
 }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Someone who writes posts, as listed in the authors file.
+///
+/// Posts refer to authors by `id`, the key of their table in the file.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Author {
+    /// Filled in from the table key; also used in `/authors/{id}`.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub bio: String,
+    /// URL of a profile picture.
+    pub avatar: Option<String>,
+    /// Elsewhere on the web, in the order given.
+    #[serde(default)]
+    pub links: Vec<AuthorLink>,
+}
+
+/// A link on an author's profile, e.g. to their GitHub or Mastodon.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AuthorLink {
+    pub title: String,
+    pub url: String,
+}
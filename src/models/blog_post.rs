@@ -1,7 +1,11 @@
-use chrono::{DateTime, Utc};
-use pulldown_cmark::{html, Event, Parser, Tag};
+use chrono::{DateTime, NaiveDate, Utc};
+use handlebars::html_escape;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use serde_json::{Map, Value};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 use syntect::highlighting::{Theme, ThemeSet};
 use syntect::html::highlighted_html_for_string;
@@ -9,14 +13,90 @@ use syntect::parsing::{SyntaxReference, SyntaxSet};
 use tokio::fs;
 use thiserror::Error;
 
+use super::author::Author;
+use super::frontmatter::{self, FrontmatterFormat};
+use super::page::PAGES_DIR;
+use super::series::MAX_SERIES_ORDER;
+use super::toc::{anchor_headings, TocEntry};
+use crate::config::{MarkdownOptions, SiteConfig};
+use crate::slug::slugify;
+
+/// Reading speed used for `reading_time_minutes`.
+pub const WORDS_PER_MINUTE: usize = 200;
+
+/// Length of an automatic excerpt, in words.
+pub const EXCERPT_WORDS: usize = 50;
+
+/// Ends the excerpt early when placed on a line of its own in a post. Inside
+/// code, or with other text on the line, it is left alone.
+pub const MORE_MARKER: &str = "<!-- more -->";
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BlogPost {
     pub slug: String,
     pub title: String,
     pub date: DateTime<Utc>,
+    /// When the post was last revised, if it has been.
+    #[serde(default)]
+    pub updated: Option<DateTime<Utc>>,
     pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Drafts are only shown in preview mode.
+    #[serde(default)]
+    pub draft: bool,
+    /// Who wrote the post, from its `author` or `authors` frontmatter.
+    #[serde(default)]
+    pub authors: Vec<Author>,
+    /// The series the post is a part of, if any.
+    #[serde(default)]
+    pub series: Option<String>,
+    /// Position in the series, from 1.
+    #[serde(default)]
+    pub series_order: Option<u32>,
     pub content: String,
     pub html_content: String,
+    /// The post's headings, nested by level.
+    #[serde(default)]
+    pub toc: Vec<TocEntry>,
+    /// Whether the post template shows the table of contents (`toc: true`).
+    #[serde(default)]
+    pub show_toc: bool,
+    /// Words of prose, not counting code blocks.
+    #[serde(default)]
+    pub word_count: usize,
+    /// Estimated at [`WORDS_PER_MINUTE`], and never less than a minute.
+    #[serde(default)]
+    pub reading_time_minutes: usize,
+    /// HTML summary: the post up to `<!-- more -->`, or its first
+    /// [`EXCERPT_WORDS`] words.
+    #[serde(default)]
+    pub excerpt: String,
+    /// Frontmatter keys without a dedicated field, available to templates.
+    #[serde(default)]
+    pub extra: Map<String, Value>,
+    /// The Markdown file this post was loaded from.
+    #[serde(skip)]
+    pub source: PathBuf,
+    /// Whether `source` is the `index.md` of a page bundle.
+    #[serde(skip)]
+    pub bundle: bool,
+    /// The Markdown extensions the post is rendered with.
+    #[serde(skip)]
+    pub markdown: MarkdownOptions,
+}
+
+/// Where a post is in its publishing lifecycle.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    Published,
+    /// Marked `draft: true` in frontmatter
+    Draft,
+    /// Dated in the future
+    Scheduled,
 }
 
 #[derive(Error, Debug)]
@@ -24,12 +104,29 @@ pub enum BlogPostError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     
-    #[error("Failed to parse frontmatter: {0}")]
-    FrontmatterParse(String),
+    #[error("Missing frontmatter: expected `---`, `+++` or `{{` at the start of the file")]
+    MissingFrontmatter,
+    
+    #[error("Failed to parse {format} frontmatter at line {line}, column {column}: {message}")]
+    FrontmatterParse {
+        format: FrontmatterFormat,
+        message: String,
+        line: usize,
+        column: usize,
+    },
     
     #[error("Missing required field: {0}")]
     MissingField(String),
     
+    #[error("Unknown author {0:?}, not listed in the authors file")]
+    UnknownAuthor(String),
+    
+    #[error("Invalid slug {0:?}: slugs cannot contain `/` or `..`")]
+    InvalidSlug(String),
+    
+    #[error("Invalid series_order {0}: parts are numbered from 1 to {MAX_SERIES_ORDER}")]
+    InvalidSeriesOrder(u32),
+    
     #[error("Syntax highlighting error: {0}")]
     SyntaxHighlighting(String),
 }
@@ -41,7 +138,7 @@ static THEME: OnceLock<Theme> = OnceLock::new();
 
 // Helper to get syntax set
 fn get_syntax_set() -> &'static SyntaxSet {
-    SYNTAX_SET.get_or_init(|| SyntaxSet::load_defaults_newlines())
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
 }
 
 // Helper to get theme
@@ -61,155 +158,448 @@ fn find_syntax(lang: &str) -> Option<&'static SyntaxReference> {
 }
 
 impl BlogPost {
-    pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, BlogPostError> {
-        let content = fs::read_to_string(path).await?;
+    /// Parses a post in `content_dir`, resolving its authors with `config`
+    /// and rendering it with the Markdown extensions `config` enables unless
+    /// the post's frontmatter overrides them.
+    pub async fn from_file<P: AsRef<Path>>(
+        path: P,
+        content_dir: &Path,
+        config: &SiteConfig,
+    ) -> Result<Self, BlogPostError> {
+        let source = path.as_ref().to_path_buf();
+        let bundle = is_bundle(&source, content_dir);
+        let content = fs::read_to_string(&source).await?;
         
-        // Simple frontmatter parsing (assumes ---yaml--- format)
-        let parts: Vec<&str> = content.split("---").collect();
-        if parts.len() < 3 {
-            return Err(BlogPostError::FrontmatterParse("Invalid frontmatter format".into()));
-        }
+        let (frontmatter, markdown_content) = frontmatter::parse(&content)?;
+        let markdown_content = markdown_content.to_string();
         
-        let frontmatter = parts[1];
-        let markdown_content = parts[2..].join("---");
+        // Frontmatter wins, but the file name can fill in the date and slug
+        let (file_date, file_slug) = filename_defaults(&source, bundle);
         
-        // Parse frontmatter (simplified)
-        let mut title = None;
-        let mut date = None;
-        let mut description = None;
-        let mut slug = None;
+        let title = frontmatter.title.ok_or_else(|| BlogPostError::MissingField("title".into()))?;
+        let date = frontmatter.date.or(file_date).ok_or_else(|| BlogPostError::MissingField("date".into()))?;
+        let slug = frontmatter
+            .slug
+            .map(validate_slug)
+            .transpose()?
+            .or(file_slug)
+            .ok_or_else(|| BlogPostError::MissingField("slug".into()))?;
         
-        for line in frontmatter.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-            
-            let parts: Vec<&str> = line.splitn(2, ':').collect();
-            if parts.len() != 2 {
-                continue;
-            }
-            
-            let key = parts[0].trim();
-            let value = parts[1].trim();
-            
-            match key {
-                "title" => title = Some(value.to_string()),
-                "date" => {
-                    date = Some(DateTime::parse_from_rfc3339(value)
-                        .map_err(|e| BlogPostError::FrontmatterParse(format!("Invalid date: {}", e)))?
-                        .with_timezone(&Utc))
-                },
-                "description" => description = Some(value.to_string()),
-                "slug" => slug = Some(value.to_string()),
-                _ => {}
-            }
-        }
-        
-        let title = title.ok_or_else(|| BlogPostError::MissingField("title".into()))?;
-        let date = date.ok_or_else(|| BlogPostError::MissingField("date".into()))?;
-        let description = description.ok_or_else(|| BlogPostError::MissingField("description".into()))?;
-        let slug = slug.ok_or_else(|| BlogPostError::MissingField("slug".into()))?;
-        
-        // Convert markdown to HTML with syntax highlighting
-        let parser = Parser::new(&markdown_content);
-        let mut html_output = String::new();
+        // An author named in both `author` and `authors` is listed once, in
+        // the place they first appear
+        let mut seen = HashSet::new();
+        let authors = frontmatter
+            .author
+            .into_iter()
+            .chain(frontmatter.authors)
+            .filter(|id| seen.insert(id.clone()))
+            .map(|id| config.author(&id).cloned().ok_or(BlogPostError::UnknownAuthor(id)))
+            .collect::<Result<Vec<_>, _>>()?;
         
-        // Process events, adding syntax highlighting for code blocks
-        let mut code_block_content = String::new();
-        let mut code_block_lang = String::new();
-        let mut in_code_block = false;
+        // Without an `updated` date, an edit on a later day than the post's
+        // date can stand in for one
+        let updated = match frontmatter.updated {
+            Some(updated) => Some(updated),
+            None if config.updated_from_mtime => modified_time(&source)
+                .await
+                .filter(|modified| modified.date_naive() > date.date_naive()),
+            None => None,
+        };
         
-        let events: Vec<_> = parser.collect();
-        let mut processed_events = Vec::new();
+        let markdown = frontmatter.markdown.apply(&config.markdown);
+        let options = markdown.parser_options();
         
-        for event in events.into_iter() {
-            match event {
-                Event::Start(Tag::CodeBlock(lang)) => {
-                    in_code_block = true;
-                    if let pulldown_cmark::CodeBlockKind::Fenced(name) = lang {
-                        code_block_lang = name.to_string();
-                    } else {
-                        code_block_lang = String::new();
-                    }
-                    code_block_content.clear();
-                },
-                Event::End(Tag::CodeBlock(_)) => {
-                    in_code_block = false;
-                    
-                    // Apply syntax highlighting
-                    if !code_block_lang.is_empty() {
-                        if let Some(syntax) = find_syntax(&code_block_lang) {
-                            match highlighted_html_for_string(
-                                &code_block_content,
-                                get_syntax_set(),
-                                syntax,
-                                get_theme()
-                            ) {
-                                Ok(highlighted_html) => {
-                                    // Replace the code block with highlighted HTML
-                                    processed_events.push(Event::Html(format!(
-                                        "<pre class=\"code-block code-{}\"><code>{}</code></pre>",
-                                        code_block_lang,
-                                        highlighted_html
-                                    ).into()));
-                                    continue;
-                                },
-                                Err(e) => {
-                                    log::warn!("Failed to highlight code: {}", e);
-                                }
-                            }
-                        }
-                    }
-                    
-                    // If no syntax highlighting was applied, fall back to regular code block
-                    let kind = if !code_block_lang.is_empty() {
-                        pulldown_cmark::CodeBlockKind::Fenced(code_block_lang.clone().into())
-                    } else {
-                        pulldown_cmark::CodeBlockKind::Indented
-                    };
-                    processed_events.push(Event::Start(Tag::CodeBlock(kind.clone())));
-                    processed_events.push(Event::Text(code_block_content.clone().into()));
-                    processed_events.push(Event::End(Tag::CodeBlock(kind)));
-                },
-                Event::Text(text) if in_code_block => {
-                    code_block_content.push_str(&text);
-                    continue;
-                },
-                _ => processed_events.push(event),
-            }
-        }
+        let word_count = plain_text(&markdown_content, options).split_whitespace().count();
         
-        html::push_html(&mut html_output, processed_events.into_iter());
+        // The excerpt's text stands in for a missing description
+        let description = frontmatter
+            .description
+            .filter(|description| !description.trim().is_empty())
+            .unwrap_or_else(|| summary_text(&markdown_content, options));
         
-        Ok(BlogPost {
+        let mut post = BlogPost {
             slug,
             title,
             date,
+            updated,
             description,
+            tags: frontmatter.tags,
+            categories: frontmatter.categories,
+            draft: frontmatter.draft,
+            authors,
+            series: frontmatter.series,
+            series_order: frontmatter.series_order.map(validate_series_order).transpose()?,
             content: markdown_content,
-            html_content: html_output,
-        })
+            html_content: String::new(),
+            toc: Vec::new(),
+            show_toc: frontmatter.toc,
+            word_count,
+            reading_time_minutes: word_count.div_ceil(WORDS_PER_MINUTE).max(1),
+            excerpt: String::new(),
+            extra: frontmatter.extra,
+            source,
+            bundle,
+            markdown,
+        };
+        post.render();
+        Ok(post)
+    }
+    
+    /// Renders `content` into `html_content`, `toc` and `excerpt`.
+    ///
+    /// Relative links in a page bundle point at its assets under the post's
+    /// current slug, so a bundle has to be rendered again when its slug
+    /// changes.
+    pub(crate) fn render(&mut self) {
+        let base = self.bundle.then(|| format!("/post/{}/", self.slug));
+        let options = self.markdown.parser_options();
+        
+        let rendered = render_markdown(&self.content, base.as_deref(), options, true);
+        self.html_content = rendered.html;
+        self.toc = rendered.toc;
+        
+        // The excerpt is everything before `<!-- more -->`, or else the
+        // opening words. Listings show many excerpts on one page, so their
+        // headings go without ids that could clash.
+        self.excerpt = match summary(&self.content) {
+            Some(before) => render_markdown(before, base.as_deref(), options, false).html,
+            None => format!("<p>{}</p>", html_escape(&summary_text(&self.content, options))),
+        };
     }
     
-    pub async fn get_all_posts() -> Result<Vec<BlogPost>, BlogPostError> {
-        let content_dir = "content";
-        let mut entries = fs::read_dir(content_dir).await?;
+    /// The post's status as of `now`.
+    pub fn status(&self, now: DateTime<Utc>) -> PostStatus {
+        if self.draft {
+            PostStatus::Draft
+        } else if self.date > now {
+            PostStatus::Scheduled
+        } else {
+            PostStatus::Published
+        }
+    }
+    
+    /// When the post last changed: its `updated` date, or else its `date`.
+    pub fn last_modified(&self) -> DateTime<Utc> {
+        self.updated.map_or(self.date, |updated| updated.max(self.date))
+    }
+    
+    /// Parses every post in `config.content_dir`.
+    pub async fn get_all_posts(config: &SiteConfig) -> Result<Vec<BlogPost>, BlogPostError> {
+        Self::load_dir(&config.content_dir, config).await
+    }
+    
+    /// Parses every Markdown file in `dir` with `config`, skipping (and
+    /// logging) any that fail.
+    pub async fn load_dir<P: AsRef<Path>>(dir: P, config: &SiteConfig) -> Result<Vec<BlogPost>, BlogPostError> {
         let mut posts = Vec::new();
         
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                match Self::from_file(&path).await {
-                    Ok(post) => posts.push(post),
-                    Err(e) => log::error!("Failed to parse blog post {:?}: {}", path, e),
-                }
+        let dir = dir.as_ref();
+        for path in markdown_files(dir, Some(&dir.join(PAGES_DIR))).await? {
+            match Self::from_file(&path, dir, config).await {
+                Ok(post) => posts.push(post),
+                Err(e) => log::error!("Failed to parse blog post {:?}: {}", path, e),
             }
         }
         
         // Sort by date, newest first
-        posts.sort_by(|a, b| b.date.cmp(&a.date));
+        posts.sort_by_key(|post| Reverse(post.date));
         
         Ok(posts)
     }
-}
\ No newline at end of file
+    
+    /// The directory holding the post's assets, if it is a page bundle
+    /// (`content/foo/index.md`).
+    pub fn bundle_dir(&self) -> Option<&Path> {
+        self.source.parent().filter(|_| self.bundle)
+    }
+    
+    /// Fenced code block languages that syntax highlighting can't resolve,
+    /// with their byte offsets into `content`.
+    pub fn unknown_languages(&self) -> Vec<(usize, String)> {
+        unknown_languages(&self.content)
+    }
+    
+    /// Slugs of the posts linked as `/post/{slug}`, with the byte offsets of
+    /// the links into `content`.
+    pub fn post_links(&self) -> Vec<(usize, String)> {
+        post_links(&self.content)
+    }
+}
+
+pub(crate) fn unknown_languages(markdown: &str) -> Vec<(usize, String)> {
+    Parser::new(markdown)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(pulldown_cmark::CodeBlockKind::Fenced(lang))) => {
+                (!lang.is_empty() && find_syntax(&lang).is_none()).then(|| (range.start, lang.to_string()))
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+pub(crate) fn post_links(markdown: &str) -> Vec<(usize, String)> {
+    Parser::new(markdown)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::Link(_, dest, _)) => {
+                let path = dest.strip_prefix("/post/")?;
+                let slug = path.split(['/', '#', '?']).next().unwrap_or_default();
+                (!slug.is_empty()).then(|| (range.start, slug.to_string()))
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Markdown rendered to HTML, with the table of contents of its headings.
+pub(crate) struct Rendered {
+    pub html: String,
+    pub toc: Vec<TocEntry>,
+}
+
+/// Renders Markdown to HTML, highlighting fenced code blocks with syntect
+/// and, with `anchors`, giving headings ids and permalinks.
+///
+/// With a `link_base`, relative links and images are resolved against it.
+/// `options` picks the extensions beyond CommonMark.
+pub(crate) fn render_markdown(markdown: &str, link_base: Option<&str>, options: Options, anchors: bool) -> Rendered {
+    // Convert markdown to HTML with syntax highlighting
+    let parser = Parser::new_ext(markdown, options);
+    let mut html_output = String::new();
+    
+    // Process events, adding syntax highlighting for code blocks
+    let mut code_block_content = String::new();
+    let mut code_block_lang = String::new();
+    let mut in_code_block = false;
+    
+    let events: Vec<_> = parser.collect();
+    let mut processed_events = Vec::new();
+    
+    for event in events.into_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(lang)) => {
+                in_code_block = true;
+                if let pulldown_cmark::CodeBlockKind::Fenced(name) = lang {
+                    code_block_lang = name.to_string();
+                } else {
+                    code_block_lang = String::new();
+                }
+                code_block_content.clear();
+            },
+            Event::End(Tag::CodeBlock(_)) => {
+                in_code_block = false;
+                
+                // Apply syntax highlighting
+                if !code_block_lang.is_empty()
+                    && let Some(syntax) = find_syntax(&code_block_lang)
+                {
+                    match highlighted_html_for_string(
+                        &code_block_content,
+                        get_syntax_set(),
+                        syntax,
+                        get_theme()
+                    ) {
+                        Ok(highlighted_html) => {
+                            // Replace the code block with highlighted HTML
+                            processed_events.push(Event::Html(format!(
+                                "<pre class=\"code-block code-{}\"><code>{}</code></pre>",
+                                code_block_lang,
+                                highlighted_html
+                            ).into()));
+                            continue;
+                        },
+                        Err(e) => {
+                            log::warn!("Failed to highlight code: {}", e);
+                        }
+                    }
+                }
+                
+                // If no syntax highlighting was applied, fall back to regular code block
+                let kind = if !code_block_lang.is_empty() {
+                    pulldown_cmark::CodeBlockKind::Fenced(code_block_lang.clone().into())
+                } else {
+                    pulldown_cmark::CodeBlockKind::Indented
+                };
+                processed_events.push(Event::Start(Tag::CodeBlock(kind.clone())));
+                processed_events.push(Event::Text(code_block_content.clone().into()));
+                processed_events.push(Event::End(Tag::CodeBlock(kind)));
+            },
+            Event::Text(text) if in_code_block => {
+                code_block_content.push_str(&text);
+                continue;
+            },
+            Event::Start(Tag::Link(kind, dest, title)) if link_base.is_some() => {
+                let dest = bundle_url(link_base, dest);
+                processed_events.push(Event::Start(Tag::Link(kind, dest, title)));
+            },
+            Event::Start(Tag::Image(kind, dest, title)) if link_base.is_some() => {
+                let dest = bundle_url(link_base, dest);
+                processed_events.push(Event::Start(Tag::Image(kind, dest, title)));
+            },
+            _ => processed_events.push(event),
+        }
+    }
+    
+    let (processed_events, toc) = if anchors {
+        anchor_headings(processed_events)
+    } else {
+        (processed_events, Vec::new())
+    };
+    html::push_html(&mut html_output, processed_events.into_iter());
+    
+    Rendered { html: html_output, toc }
+}
+
+async fn modified_time(path: &Path) -> Option<DateTime<Utc>> {
+    let modified = fs::metadata(path).await.ok()?.modified().ok()?;
+    Some(modified.into())
+}
+
+// The text a reader sees, without markup or code blocks
+fn plain_text(markdown: &str, options: Options) -> String {
+    let mut text = String::new();
+    let mut in_code_block = false;
+    
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(t) | Event::Code(t) if !in_code_block => text.push_str(&t),
+            Event::SoftBreak
+            | Event::HardBreak
+            | Event::End(Tag::Paragraph | Tag::Heading(..) | Tag::Item | Tag::TableCell) => text.push(' '),
+            _ => {}
+        }
+    }
+    
+    text
+}
+
+// The Markdown before `<!-- more -->`, if the post has the marker on a line
+// of its own. In code, or in the middle of a line, it is just text.
+fn summary(markdown: &str) -> Option<&str> {
+    Parser::new(markdown).into_offset_iter().find_map(|(event, range)| match event {
+        Event::Html(html) if html.trim() == MORE_MARKER => {
+            let before = &markdown[..range.start];
+            (before.is_empty() || before.ends_with('\n')).then_some(before)
+        },
+        _ => None,
+    })
+}
+
+// The text of the summary, or of the opening words without one
+fn summary_text(markdown: &str, options: Options) -> String {
+    first_words(&plain_text(summary(markdown).unwrap_or(markdown), options), EXCERPT_WORDS)
+}
+
+// The first `count` words of `text`, with an ellipsis if there were more
+fn first_words(text: &str, count: usize) -> String {
+    let mut words = text.split_whitespace();
+    let mut excerpt = words.by_ref().take(count).collect::<Vec<_>>().join(" ");
+    if words.next().is_some() {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+// A page bundle is a directory with an `index.md` and the post's assets. An
+// `index.md` directly in the content directory is an ordinary post.
+pub(crate) fn is_bundle(source: &Path, root: &Path) -> bool {
+    source.file_name().is_some_and(|name| name == "index.md") && source.parent() != Some(root)
+}
+
+// Points a relative link or image at the bundle's assets
+fn bundle_url<'a>(base: Option<&str>, dest: CowStr<'a>) -> CowStr<'a> {
+    let is_relative = !dest.is_empty()
+        && !dest.starts_with(['/', '#', '?'])
+        && !dest.contains(':');
+    match base {
+        Some(base) if is_relative => format!("{}{}", base, dest.trim_start_matches("./")).into(),
+        _ => dest,
+    }
+}
+
+// Jekyll-style names like `2024-03-01-hello-world.md` give a date and the
+// slug `hello-world`; any other name just gives a slug. Bundles are named
+// after their directory.
+pub(crate) fn filename_defaults(path: &Path, bundle: bool) -> (Option<DateTime<Utc>>, Option<String>) {
+    let file = if bundle { path.parent().unwrap_or(path) } else { path };
+    let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    
+    let date = stem
+        .get(..10)
+        .filter(|_| stem.get(10..11).is_none_or(|sep| sep == "-"))
+        .and_then(|prefix| NaiveDate::parse_from_str(prefix, "%Y-%m-%d").ok());
+    let name = match date {
+        Some(_) if stem.len() > 11 => &stem[11..],
+        _ => stem,
+    };
+    
+    let slug = slugify(name);
+    let date = date.and_then(|date| date.and_hms_opt(0, 0, 0)).map(|date| date.and_utc());
+    (date, (!slug.is_empty()).then_some(slug))
+}
+
+// Slugs become URL paths and, in `andy build`, directory names, so they
+// must stay a single path component
+pub(crate) fn validate_slug(slug: String) -> Result<String, BlogPostError> {
+    if slug.contains('/') || slug.contains("..") {
+        return Err(BlogPostError::InvalidSlug(slug));
+    }
+    Ok(slug)
+}
+
+fn validate_series_order(order: u32) -> Result<u32, BlogPostError> {
+    if !(1..=MAX_SERIES_ORDER).contains(&order) {
+        return Err(BlogPostError::InvalidSeriesOrder(order));
+    }
+    Ok(order)
+}
+
+/// The post or page sources in `dir` and its subdirectories (see
+/// [`is_source`]), sorted by path. The directory `skip` is left out.
+pub(crate) async fn markdown_files(dir: &Path, skip: Option<&Path>) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    
+    while let Some(current) = pending.pop() {
+        let mut entries = fs::read_dir(&current).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                if Some(path.as_path()) != skip && !entry.file_name().to_string_lossy().starts_with('.') {
+                    pending.push(path);
+                }
+            } else if is_source(dir, &path) {
+                files.push(path);
+            }
+        }
+    }
+    
+    files.sort();
+    Ok(files)
+}
+
+/// Whether `path` is a post or page in `root`, as opposed to something to
+/// ignore: it must be a Markdown file, with no hidden file or directory in
+/// its path. Inside a page bundle only the `index.md` counts; the rest of the
+/// directory is its assets.
+pub(crate) fn is_source(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    if path.extension().and_then(|s| s.to_str()) != Some("md")
+        || relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+    {
+        return false;
+    }
+    
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| *dir != root)
+        .all(|dir| {
+            let index = dir.join("index.md");
+            index == path || !index.is_file()
+        })
+}
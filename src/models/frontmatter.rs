@@ -0,0 +1,279 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::fmt;
+
+use super::blog_post::BlogPostError;
+use crate::config::MarkdownOptions;
+
+/// The syntax a post's frontmatter is written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrontmatterFormat {
+    /// Delimited by `---` lines (Jekyll, Hugo)
+    Yaml,
+    /// Delimited by `+++` lines (Hugo, Zola)
+    Toml,
+    /// A JSON object at the very start of the file (Hugo)
+    Json,
+}
+
+impl fmt::Display for FrontmatterFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FrontmatterFormat::Yaml => "YAML",
+            FrontmatterFormat::Toml => "TOML",
+            FrontmatterFormat::Json => "JSON",
+        })
+    }
+}
+
+/// The metadata block at the top of a post or page.
+///
+/// Known keys are typed; everything else is kept in `extra` so templates can
+/// use arbitrary per-post data.
+#[derive(Deserialize, Debug, Default)]
+pub(crate) struct Frontmatter {
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub date: Option<DateTime<Utc>>,
+    /// When the post was last corrected or revised
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub updated: Option<DateTime<Utc>>,
+    pub description: Option<String>,
+    pub slug: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub draft: bool,
+    /// Id of the post's author in the authors file
+    pub author: Option<String>,
+    /// Ids of several authors, for posts written together
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// Name of the multi-part series the post belongs to
+    pub series: Option<String>,
+    /// The post's part number in its series, from 1
+    pub series_order: Option<u32>,
+    /// Show a table of contents above the post
+    #[serde(default)]
+    pub toc: bool,
+    /// Markdown extensions to turn on or off for this post alone
+    #[serde(default)]
+    pub markdown: MarkdownOverrides,
+    /// Pages only: list the page in the site navigation
+    #[serde(default)]
+    pub menu: bool,
+    /// Pages only: position in the navigation, lowest first
+    pub menu_order: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Per-post changes to the site's [`MarkdownOptions`]; unset keys keep the
+/// site setting.
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct MarkdownOverrides {
+    pub tables: Option<bool>,
+    pub footnotes: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub tasklists: Option<bool>,
+    pub smart_punctuation: Option<bool>,
+}
+
+impl MarkdownOverrides {
+    pub fn apply(&self, site: &MarkdownOptions) -> MarkdownOptions {
+        MarkdownOptions {
+            tables: self.tables.unwrap_or(site.tables),
+            footnotes: self.footnotes.unwrap_or(site.footnotes),
+            strikethrough: self.strikethrough.unwrap_or(site.strikethrough),
+            tasklists: self.tasklists.unwrap_or(site.tasklists),
+            smart_punctuation: self.smart_punctuation.unwrap_or(site.smart_punctuation),
+        }
+    }
+}
+
+/// Splits a post into its frontmatter and Markdown body and parses the
+/// frontmatter.
+///
+/// The format is picked from the start of the file: `---` for YAML, `+++` for
+/// TOML or `{` for JSON. Delimited frontmatter runs up to the next line
+/// consisting only of the same delimiter, so delimiters appearing later in the
+/// body (e.g. horizontal rules) are left alone.
+pub(crate) fn parse(content: &str) -> Result<(Frontmatter, &str), BlogPostError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    if content.starts_with('{') {
+        return parse_json(content);
+    }
+
+    let format = if content.starts_with("---") {
+        FrontmatterFormat::Yaml
+    } else if content.starts_with("+++") {
+        FrontmatterFormat::Toml
+    } else {
+        return Err(BlogPostError::MissingFrontmatter);
+    };
+    let delimiter = &content[..3];
+
+    let Some((frontmatter, body)) = split(content, delimiter) else {
+        return Err(BlogPostError::FrontmatterParse {
+            format,
+            message: format!("missing closing `{}`", delimiter),
+            line: 1,
+            column: 1,
+        });
+    };
+
+    if frontmatter.trim().is_empty() {
+        return Ok((Frontmatter::default(), body));
+    }
+
+    let frontmatter = match format {
+        FrontmatterFormat::Yaml => serde_yaml::from_str(frontmatter).map_err(yaml_error)?,
+        _ => toml::from_str(frontmatter).map_err(|e| toml_error(frontmatter, e))?,
+    };
+
+    Ok((frontmatter, body))
+}
+
+// Returns the text between an opening delimiter line and the matching closing
+// delimiter line, and everything after it
+fn split<'a>(content: &'a str, delimiter: &str) -> Option<(&'a str, &'a str)> {
+    let rest = content.strip_prefix(delimiter)?;
+    let rest = rest
+        .strip_prefix("\r\n")
+        .or_else(|| rest.strip_prefix('\n'))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+// JSON frontmatter has no delimiters: the body starts right after the object
+fn parse_json(content: &str) -> Result<(Frontmatter, &str), BlogPostError> {
+    let mut stream = serde_json::Deserializer::from_str(content).into_iter::<Frontmatter>();
+
+    match stream.next() {
+        Some(Ok(frontmatter)) => Ok((frontmatter, &content[stream.byte_offset()..])),
+        Some(Err(e)) => Err(BlogPostError::FrontmatterParse {
+            format: FrontmatterFormat::Json,
+            // serde_json appends its own location to the message
+            message: strip_location(e.to_string(), " at line "),
+            line: e.line(),
+            column: e.column(),
+        }),
+        None => Err(BlogPostError::MissingFrontmatter),
+    }
+}
+
+fn yaml_error(err: serde_yaml::Error) -> BlogPostError {
+    let message = err.to_string();
+    let (message, line, column) = match err.location() {
+        Some(loc) => {
+            // Drop serde_yaml's own location, which is relative to the
+            // frontmatter rather than the file. The YAML starts on the line
+            // after the opening delimiter.
+            (strip_location(message, " at line "), loc.line() + 1, loc.column())
+        }
+        None => (message, 1, 1),
+    };
+
+    BlogPostError::FrontmatterParse {
+        format: FrontmatterFormat::Yaml,
+        message,
+        line,
+        column,
+    }
+}
+
+fn toml_error(frontmatter: &str, err: toml::de::Error) -> BlogPostError {
+    let (line, column) = match err.span() {
+        Some(span) => {
+            let before = &frontmatter[..span.start];
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            // The TOML starts on the line after the opening delimiter
+            (before.matches('\n').count() + 2, before[line_start..].chars().count() + 1)
+        }
+        None => (1, 1),
+    };
+
+    BlogPostError::FrontmatterParse {
+        format: FrontmatterFormat::Toml,
+        message: err.message().trim_end().to_string(),
+        line,
+        column,
+    }
+}
+
+fn strip_location(mut message: String, marker: &str) -> String {
+    if let Some(i) = message.rfind(marker) {
+        message.truncate(i);
+    }
+    message
+}
+
+// Dates may be RFC 3339 timestamps, or (as Hugo and Zola allow) a local date
+// time or a bare date, both taken as UTC. TOML's native datetimes arrive as a
+// single-entry map. A null or blank value counts as no date at all.
+fn deserialize_date<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct DateVisitor;
+
+    impl<'de> Visitor<'de> for DateVisitor {
+        type Value = Option<DateTime<Utc>>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an RFC 3339 date")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            if value.trim().is_empty() {
+                return Ok(None);
+            }
+            parse_date(value).map(Some).ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            match map.next_entry::<String, String>()? {
+                Some((_, value)) => self.visit_str(&value),
+                None => Err(de::Error::invalid_length(0, &self)),
+            }
+        }
+    }
+
+    deserializer.deserialize_any(DateVisitor)
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        return Some(date.and_utc());
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+}
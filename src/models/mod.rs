@@ -1,3 +1,19 @@
+mod author;
 mod blog_post;
+mod frontmatter;
+mod page;
+mod post_store;
+mod series;
+mod taxonomy;
+mod toc;
 
-pub use blog_post::BlogPost;
\ No newline at end of file
+pub use author::{Author, AuthorLink};
+pub use blog_post::{BlogPost, BlogPostError, PostStatus, EXCERPT_WORDS, MORE_MARKER, WORDS_PER_MINUTE};
+pub(crate) use blog_post::{is_source, markdown_files, post_links, unknown_languages};
+pub use frontmatter::FrontmatterFormat;
+pub use page::{Page, DEFAULT_MENU_ORDER, PAGES_DIR};
+pub use post_store::{DuplicateSlug, PostStore, Visibility};
+pub use series::{Series, SeriesWarning, MAX_SERIES_ORDER};
+pub(crate) use series::series_warnings;
+pub use taxonomy::{Taxonomy, Term};
+pub use toc::TocEntry;
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use super::blog_post::{filename_defaults, is_bundle, markdown_files, render_markdown, validate_slug, BlogPostError};
+use super::frontmatter;
+use crate::config::SiteConfig;
+
+/// Subdirectory of the content directory holding standalone pages.
+pub const PAGES_DIR: &str = "pages";
+
+/// Navigation position of pages that don't set `menu_order`, after the
+/// built-in links.
+pub const DEFAULT_MENU_ORDER: i64 = 100;
+
+/// A standalone page such as About, served at `/{slug}`.
+///
+/// Pages go through the same Markdown pipeline as posts but have no date,
+/// tags or feed entries. Like posts they can be page bundles
+/// (`pages/team/index.md`), with their assets served under `/{slug}/`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Page {
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub content: String,
+    pub html_content: String,
+    /// Listed in the site navigation.
+    #[serde(default)]
+    pub menu: bool,
+    /// Position in the navigation, lowest first.
+    #[serde(default)]
+    pub menu_order: i64,
+    /// Frontmatter keys without a dedicated field, available to templates.
+    #[serde(default)]
+    pub extra: Map<String, Value>,
+    /// The Markdown file this page was loaded from.
+    #[serde(skip)]
+    pub source: PathBuf,
+    /// Whether `source` is the `index.md` of a page bundle.
+    #[serde(skip)]
+    pub bundle: bool,
+}
+
+impl Page {
+    /// Parses a page in `pages_dir`, rendering it with the Markdown extensions
+    /// `config` enables unless the page's frontmatter overrides them.
+    pub async fn from_file<P: AsRef<Path>>(
+        path: P,
+        pages_dir: &Path,
+        config: &SiteConfig,
+    ) -> Result<Self, BlogPostError> {
+        let source = path.as_ref().to_path_buf();
+        let content = fs::read_to_string(&source).await?;
+
+        let (frontmatter, markdown_content) = frontmatter::parse(&content)?;
+        let bundle = is_bundle(&source, pages_dir);
+        let (_, file_slug) = filename_defaults(&source, bundle);
+
+        let title = frontmatter.title.ok_or_else(|| BlogPostError::MissingField("title".into()))?;
+        let slug = frontmatter
+            .slug
+            .map(validate_slug)
+            .transpose()?
+            .or(file_slug)
+            .ok_or_else(|| BlogPostError::MissingField("slug".into()))?;
+
+        // Relative links in a page bundle point at its assets
+        let bundle_base = bundle.then(|| format!("/{}/", slug));
+        let options = frontmatter.markdown.apply(&config.markdown).parser_options();
+
+        Ok(Page {
+            slug,
+            title,
+            description: frontmatter.description.unwrap_or_default(),
+            content: markdown_content.to_string(),
+            html_content: render_markdown(markdown_content, bundle_base.as_deref(), options, true).html,
+            menu: frontmatter.menu,
+            menu_order: frontmatter.menu_order.unwrap_or(DEFAULT_MENU_ORDER),
+            extra: frontmatter.extra,
+            source,
+            bundle,
+        })
+    }
+
+    /// The directory holding the page's assets, if it is a page bundle.
+    pub fn bundle_dir(&self) -> Option<&Path> {
+        self.source.parent().filter(|_| self.bundle)
+    }
+
+    /// Parses every page in `dir` with `config`, skipping (and logging) any
+    /// that fail. A missing directory just means there are no pages.
+    pub async fn load_dir<P: AsRef<Path>>(dir: P, config: &SiteConfig) -> Result<Vec<Page>, BlogPostError> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut pages = Vec::new();
+        for path in markdown_files(dir, None).await? {
+            match Self::from_file(&path, dir, config).await {
+                Ok(page) => pages.push(page),
+                Err(e) => log::error!("Failed to parse page {:?}: {}", path, e),
+            }
+        }
+        Ok(pages)
+    }
+}
@@ -0,0 +1,358 @@
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::blog_post::{BlogPost, BlogPostError, PostStatus};
+use super::page::{Page, PAGES_DIR};
+use super::series::{series_warnings, Series, SeriesWarning};
+use super::taxonomy::{Taxonomy, Term};
+use crate::config::SiteConfig;
+use crate::slug::slugify;
+
+/// Which posts a query returns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Visibility {
+    /// Only posts that are live right now
+    #[default]
+    Published,
+    /// Drafts and scheduled posts as well
+    Preview,
+}
+
+impl Visibility {
+    /// Whether `post` should be shown.
+    ///
+    /// Scheduled posts are checked against the current time, so they show up
+    /// as soon as their date passes.
+    pub fn includes(self, post: &BlogPost) -> bool {
+        self == Visibility::Preview || post.status(Utc::now()) == PostStatus::Published
+    }
+}
+
+/// Two posts that asked for the same slug.
+///
+/// The older post keeps the slug and the newer one is served with a numeric
+/// suffix, so publishing a post can never hide one that is already live.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateSlug {
+    pub slug: String,
+    /// The post that kept the slug
+    pub kept: PathBuf,
+    /// The post that was given `new_slug` instead
+    pub renamed: PathBuf,
+    pub new_slug: String,
+}
+
+/// All blog posts and pages, parsed and rendered once and kept in memory.
+///
+/// Posts are stored newest first and indexed by slug so handlers never have
+/// to touch the filesystem.
+#[derive(Debug, Default)]
+pub struct PostStore {
+    posts: Vec<BlogPost>,
+    by_slug: HashMap<String, usize>,
+    /// The slug each file asked for, before any renaming
+    requested: HashMap<PathBuf, String>,
+    duplicates: Vec<DuplicateSlug>,
+    series_warnings: Vec<SeriesWarning>,
+    pages: Vec<Page>,
+}
+
+impl PostStore {
+    /// Loads and renders every post in `dir`, and every page in its `pages`
+    /// subdirectory, with `config`.
+    pub async fn load<P: AsRef<Path>>(dir: P, config: &SiteConfig) -> Result<Self, BlogPostError> {
+        let posts = BlogPost::load_dir(&dir, config).await?;
+        let mut store = Self::from_posts(posts);
+        for page in Page::load_dir(dir.as_ref().join(PAGES_DIR), config).await? {
+            store.insert_page(page);
+        }
+        Ok(store)
+    }
+    
+    /// Builds a store from already parsed posts, sorting them newest first.
+    pub fn from_posts(posts: Vec<BlogPost>) -> Self {
+        let requested = posts.iter().map(|post| (post.source.clone(), post.slug.clone())).collect();
+        let mut store = Self {
+            posts,
+            by_slug: HashMap::new(),
+            requested,
+            duplicates: Vec::new(),
+            series_warnings: Vec::new(),
+            pages: Vec::new(),
+        };
+        store.reindex();
+        store
+    }
+    
+    /// Adds a post, replacing any post previously loaded from the same file.
+    pub fn insert(&mut self, post: BlogPost) {
+        self.posts.retain(|p| p.source != post.source);
+        self.requested.insert(post.source.clone(), post.slug.clone());
+        self.posts.push(post);
+        self.reindex();
+    }
+    
+    /// Drops the post loaded from `source`, if any.
+    pub fn remove(&mut self, source: &Path) -> Option<BlogPost> {
+        let i = self.posts.iter().position(|p| p.source == source)?;
+        let post = self.posts.remove(i);
+        self.requested.remove(source);
+        self.reindex();
+        Some(post)
+    }
+    
+    // Re-sorts the posts, renames duplicate slugs, rebuilds the slug index
+    // and checks the numbering of series
+    fn reindex(&mut self) {
+        self.dedupe_slugs();
+        self.posts.sort_by_key(|post| Reverse(post.date));
+        
+        self.series_warnings = series_warnings(&self.posts);
+        for warning in &self.series_warnings {
+            log::warn!("{:?}: {}", warning.source, warning.message);
+        }
+        
+        self.by_slug.clear();
+        for (i, post) in self.posts.iter().enumerate() {
+            self.by_slug.insert(post.slug.clone(), i);
+        }
+    }
+    
+    // Gives every post after the first (oldest) to claim a slug a free
+    // `{slug}-{n}` instead, so the outcome doesn't depend on load order
+    fn dedupe_slugs(&mut self) {
+        self.posts.sort_by(|a, b| (a.date, &a.source).cmp(&(b.date, &b.source)));
+        
+        // Start over from the requested slugs so earlier renames don't stick
+        let previous: Vec<String> = self.posts.iter().map(|post| post.slug.clone()).collect();
+        for post in &mut self.posts {
+            if let Some(slug) = self.requested.get(&post.source) {
+                post.slug.clone_from(slug);
+            }
+        }
+        
+        let mut taken: HashSet<String> = self.posts.iter().map(|post| post.slug.clone()).collect();
+        let mut owners: HashMap<String, PathBuf> = HashMap::new();
+        self.duplicates.clear();
+        
+        for post in &mut self.posts {
+            let Some(kept) = owners.get(&post.slug) else {
+                owners.insert(post.slug.clone(), post.source.clone());
+                continue;
+            };
+            
+            let new_slug = (2..)
+                .map(|n| format!("{}-{}", post.slug, n))
+                .find(|slug| !taken.contains(slug))
+                .expect("some suffix is always free");
+            log::warn!(
+                "Duplicate slug {:?} in {:?}, already used by {:?}; serving it as {:?}",
+                post.slug, post.source, kept, new_slug
+            );
+            self.duplicates.push(DuplicateSlug {
+                slug: std::mem::replace(&mut post.slug, new_slug.clone()),
+                kept: kept.clone(),
+                renamed: post.source.clone(),
+                new_slug: new_slug.clone(),
+            });
+            taken.insert(new_slug.clone());
+            owners.insert(new_slug, post.source.clone());
+        }
+        
+        // Bundles link to their assets under the slug they are served at
+        for (post, previous) in self.posts.iter_mut().zip(previous) {
+            if post.bundle && post.slug != previous {
+                post.render();
+            }
+        }
+    }
+    
+    /// Slugs that more than one post asked for, and how they were resolved.
+    pub fn duplicates(&self) -> &[DuplicateSlug] {
+        &self.duplicates
+    }
+    
+    /// Gaps and repeated part numbers found in series.
+    pub fn series_warnings(&self) -> &[SeriesWarning] {
+        &self.series_warnings
+    }
+    
+    /// All posts, newest first, including drafts and scheduled posts.
+    pub fn all(&self) -> &[BlogPost] {
+        &self.posts
+    }
+    
+    /// The posts visible with `visibility`, newest first.
+    pub fn posts(&self, visibility: Visibility) -> Vec<&BlogPost> {
+        self.posts.iter().filter(|post| visibility.includes(post)).collect()
+    }
+    
+    /// The `n` most recent posts visible with `visibility`.
+    pub fn latest(&self, visibility: Visibility, n: usize) -> Vec<&BlogPost> {
+        self.posts.iter().filter(|post| visibility.includes(post)).take(n).collect()
+    }
+    
+    /// Looks up a post by its slug, if it is visible with `visibility`.
+    pub fn get(&self, slug: &str, visibility: Visibility) -> Option<&BlogPost> {
+        let post = &self.posts[*self.by_slug.get(slug)?];
+        visibility.includes(post).then_some(post)
+    }
+    
+    /// The posts visible with `visibility` that were published just before
+    /// and just after `post`, as `(previous, next)`.
+    pub fn neighbours(&self, post: &BlogPost, visibility: Visibility) -> (Option<&BlogPost>, Option<&BlogPost>) {
+        // Posts from the same moment are ordered by file so none are skipped
+        fn key(post: &BlogPost) -> (DateTime<Utc>, &Path) {
+            (post.date, &post.source)
+        }
+        
+        let posts = self.posts(visibility);
+        let previous = posts.iter().copied().filter(|other| key(other) < key(post)).max_by_key(|other| key(other));
+        let next = posts.iter().copied().filter(|other| key(other) > key(post)).min_by_key(|other| key(other));
+        (previous, next)
+    }
+    
+    /// Up to `limit` other posts most like `post`: those sharing the most
+    /// tags first, then those whose titles and descriptions have the most
+    /// words in common. Posts with nothing in common are left out.
+    pub fn related(&self, post: &BlogPost, visibility: Visibility, limit: usize) -> Vec<&BlogPost> {
+        let tags = tag_slugs(post);
+        let words = keywords(post);
+        
+        let mut scored: Vec<_> = self
+            .posts(visibility)
+            .into_iter()
+            .filter(|other| other.source != post.source)
+            .map(|other| {
+                let shared = tag_slugs(other).intersection(&tags).count();
+                (shared, similarity(&words, &keywords(other)), other)
+            })
+            .filter(|&(shared, similarity, _)| shared > 0 || similarity > 0.0)
+            .collect();
+        
+        // The sort is stable, so newer posts win ties
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+        scored.into_iter().take(limit).map(|(_, _, other)| other).collect()
+    }
+    
+    /// Every term used in `taxonomy`, sorted by slug, with post counts.
+    ///
+    /// Terms are matched by slug, so `Rust` and `rust` are the same tag; the
+    /// spelling from the newest post wins.
+    pub fn terms(&self, taxonomy: Taxonomy, visibility: Visibility) -> Vec<Term> {
+        let mut terms: Vec<Term> = Vec::new();
+        let mut by_slug: HashMap<String, usize> = HashMap::new();
+        
+        for post in self.posts(visibility) {
+            for name in taxonomy.terms(post) {
+                let slug = slugify(name);
+                match by_slug.get(&slug) {
+                    Some(&i) => terms[i].count += 1,
+                    None => {
+                        by_slug.insert(slug.clone(), terms.len());
+                        terms.push(Term { name: name.clone(), slug, count: 1 });
+                    }
+                }
+            }
+        }
+        
+        terms.sort_by(|a, b| a.slug.cmp(&b.slug));
+        terms
+    }
+    
+    /// The term with the given slug and its posts, newest first.
+    pub fn term(
+        &self,
+        taxonomy: Taxonomy,
+        slug: &str,
+        visibility: Visibility,
+    ) -> Option<(Term, Vec<&BlogPost>)> {
+        let find = |post: &BlogPost| {
+            taxonomy.terms(post).iter().find(|term| slugify(term) == slug).cloned()
+        };
+        let mut posts = self.posts(visibility);
+        posts.retain(|post| find(post).is_some());
+        let name = find(posts.first()?);
+        
+        let term = Term { name: name?, slug: slug.to_string(), count: posts.len() };
+        Some((term, posts))
+    }
+    
+    /// The posts visible with `visibility` that the author with `id` wrote or
+    /// co-wrote, newest first.
+    pub fn author_posts(&self, id: &str, visibility: Visibility) -> Vec<&BlogPost> {
+        let mut posts = self.posts(visibility);
+        posts.retain(|post| post.authors.iter().any(|author| author.id == id));
+        posts
+    }
+    
+    /// Every series with a post visible with `visibility`, sorted by slug.
+    pub fn all_series(&self, visibility: Visibility) -> Vec<Series<'_>> {
+        Series::group(self.posts(visibility))
+    }
+    
+    /// The series with the given slug, with its visible parts in order.
+    pub fn series(&self, slug: &str, visibility: Visibility) -> Option<Series<'_>> {
+        self.all_series(visibility).into_iter().find(|series| series.slug == slug)
+    }
+    
+    /// All standalone pages, sorted by slug.
+    pub fn pages(&self) -> &[Page] {
+        &self.pages
+    }
+    
+    /// Looks up a page by its slug.
+    pub fn page(&self, slug: &str) -> Option<&Page> {
+        self.pages.iter().find(|page| page.slug == slug)
+    }
+    
+    /// Adds a page, replacing any page previously loaded from the same file.
+    pub fn insert_page(&mut self, page: Page) {
+        self.pages.retain(|p| p.source != page.source);
+        if let Some(other) = self.page(&page.slug) {
+            log::warn!("Duplicate page slug {:?} in {:?} and {:?}", page.slug, other.source, page.source);
+        }
+        self.pages.push(page);
+        self.pages.sort_by(|a, b| (&a.slug, &a.source).cmp(&(&b.slug, &b.source)));
+    }
+    
+    /// Drops the page loaded from `source`, if any.
+    pub fn remove_page(&mut self, source: &Path) -> Option<Page> {
+        let i = self.pages.iter().position(|p| p.source == source)?;
+        Some(self.pages.remove(i))
+    }
+    
+    pub fn len(&self) -> usize {
+        self.posts.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.posts.is_empty()
+    }
+}
+
+fn tag_slugs(post: &BlogPost) -> HashSet<String> {
+    post.tags.iter().map(|tag| slugify(tag)).collect()
+}
+
+// Lowercased words of the title and description, skipping short ones as a
+// cheap way to ignore "the", "and" and the like
+fn keywords(post: &BlogPost) -> HashSet<String> {
+    [&post.title, &post.description]
+        .into_iter()
+        .flat_map(|text| text.split(|c: char| !c.is_alphanumeric()))
+        .filter(|word| word.chars().count() > 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// Jaccard index: the share of all words that both sets have
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let all = a.union(b).count();
+    if all == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / all as f64
+}
@@ -0,0 +1,109 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use super::blog_post::BlogPost;
+use crate::slug::slugify;
+
+/// A multi-part series of posts, grouped by the `series` frontmatter key.
+#[derive(Serialize, Clone, Debug)]
+pub struct Series<'a> {
+    pub name: String,
+    /// The name as it appears in `/series/{slug}`.
+    pub slug: String,
+    /// The parts in reading order: by `series_order`, then by date.
+    pub posts: Vec<&'a BlogPost>,
+}
+
+impl<'a> Series<'a> {
+    /// Groups `posts` into series, sorted by slug.
+    ///
+    /// Series are matched by slug, so `Async Rust` and `async-rust` are the
+    /// same series; the spelling from the first part wins.
+    pub(crate) fn group(posts: impl IntoIterator<Item = &'a BlogPost>) -> Vec<Series<'a>> {
+        let mut by_slug: BTreeMap<String, Vec<&BlogPost>> = BTreeMap::new();
+        for post in posts {
+            if let Some(name) = &post.series {
+                by_slug.entry(slugify(name)).or_default().push(post);
+            }
+        }
+
+        by_slug
+            .into_iter()
+            .map(|(slug, mut posts)| {
+                posts.sort_by(|a, b| {
+                    let key = |post: &BlogPost| (post.series_order.is_none(), post.series_order, post.date);
+                    key(a).cmp(&key(b)).then_with(|| a.source.cmp(&b.source))
+                });
+                let name = posts[0].series.clone().unwrap_or_default();
+                Series { name, slug, posts }
+            })
+            .collect()
+    }
+
+    /// Which part `post` is, counting from 1.
+    pub fn part(&self, post: &BlogPost) -> Option<usize> {
+        self.posts.iter().position(|p| p.source == post.source).map(|i| i + 1)
+    }
+}
+
+/// The highest `series_order` a post may give; anything above it is almost
+/// certainly a typo.
+pub const MAX_SERIES_ORDER: u32 = 1000;
+
+/// A numbering problem in a series, reported against the post at `source`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeriesWarning {
+    pub source: PathBuf,
+    pub message: String,
+}
+
+/// Finds gaps and repeated numbers in the `series_order` of every series.
+///
+/// Drafts and scheduled posts count, so an unpublished part doesn't show up
+/// as a gap.
+pub(crate) fn series_warnings(posts: &[BlogPost]) -> Vec<SeriesWarning> {
+    let mut warnings = Vec::new();
+
+    for series in Series::group(posts) {
+        let mut claimed: HashMap<u32, &BlogPost> = HashMap::new();
+        for post in &series.posts {
+            let Some(order) = post.series_order else {
+                continue;
+            };
+            match claimed.get(&order) {
+                Some(first) => warnings.push(SeriesWarning {
+                    source: post.source.clone(),
+                    message: format!(
+                        "Part {} of series {:?} is also claimed by {}",
+                        order, series.name, first.source.display()
+                    ),
+                }),
+                None => {
+                    claimed.insert(order, post);
+                }
+            }
+        }
+
+        // Report each run of missing parts against the part that follows it
+        let mut orders: Vec<u32> = claimed.keys().copied().collect();
+        orders.sort_unstable();
+        let mut previous = 0;
+        for order in orders {
+            if order > previous + 1 {
+                let missing = if order == previous + 2 {
+                    format!("part {}", previous + 1)
+                } else {
+                    format!("parts {}–{}", previous + 1, order - 1)
+                };
+                warnings.push(SeriesWarning {
+                    source: claimed[&order].source.clone(),
+                    message: format!("Series {:?} has no {}", series.name, missing),
+                });
+            }
+            previous = order;
+        }
+    }
+
+    warnings
+}
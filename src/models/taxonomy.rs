@@ -0,0 +1,52 @@
+use serde::Serialize;
+
+use super::blog_post::BlogPost;
+
+/// A way of grouping posts, set per post in frontmatter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Taxonomy {
+    Tags,
+    Categories,
+}
+
+impl Taxonomy {
+    /// The taxonomy's plural name, also used as its URL prefix.
+    pub fn name(self) -> &'static str {
+        match self {
+            Taxonomy::Tags => "tags",
+            Taxonomy::Categories => "categories",
+        }
+    }
+    
+    /// Human readable name for a single term, e.g. in page titles.
+    pub fn singular(self) -> &'static str {
+        match self {
+            Taxonomy::Tags => "Tag",
+            Taxonomy::Categories => "Category",
+        }
+    }
+    
+    /// Human readable name for the whole taxonomy.
+    pub fn plural(self) -> &'static str {
+        match self {
+            Taxonomy::Tags => "Tags",
+            Taxonomy::Categories => "Categories",
+        }
+    }
+    
+    /// The terms `post` is filed under in this taxonomy.
+    pub fn terms(self, post: &BlogPost) -> &[String] {
+        match self {
+            Taxonomy::Tags => &post.tags,
+            Taxonomy::Categories => &post.categories,
+        }
+    }
+}
+
+/// A single tag or category together with how many posts use it.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Term {
+    pub name: String,
+    pub slug: String,
+    pub count: usize,
+}
@@ -0,0 +1,90 @@
+use pulldown_cmark::{Event, Tag};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::slug::slugify;
+
+/// A heading in a post's table of contents.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TocEntry {
+    /// 1 for `#`, 2 for `##` and so on.
+    pub level: u8,
+    pub text: String,
+    /// The heading's `id`, so `#{id}` links to it.
+    pub id: String,
+    /// Deeper headings up to the next heading of this level or higher.
+    #[serde(default)]
+    pub children: Vec<TocEntry>,
+}
+
+/// Gives every heading in `events` an `id` and a permalink anchor, and
+/// collects them into a table of contents.
+///
+/// Ids are slugs of the heading text. Repeated headings get `-1`, `-2`, ...
+/// appended so every id on the page is unique.
+pub(crate) fn anchor_headings(events: Vec<Event<'_>>) -> (Vec<Event<'_>>, Vec<TocEntry>) {
+    let mut output = Vec::with_capacity(events.len());
+    let mut toc = Vec::new();
+    let mut used = HashSet::new();
+
+    let mut events = events.into_iter();
+    while let Some(event) = events.next() {
+        let Event::Start(Tag::Heading(level, _, _)) = event else {
+            output.push(event);
+            continue;
+        };
+
+        // Buffer the heading's contents to get at its text
+        let mut inner = Vec::new();
+        let mut text = String::new();
+        for event in events.by_ref() {
+            match &event {
+                Event::End(Tag::Heading(..)) => break,
+                Event::Text(t) | Event::Code(t) => text.push_str(t),
+                _ => {}
+            }
+            inner.push(event);
+        }
+
+        let id = unique_id(&mut used, &text);
+        output.push(Event::Html(format!("<{} id=\"{}\">", level, id).into()));
+        output.extend(inner);
+        output.push(Event::Html(format!(
+            "<a class=\"heading-anchor\" href=\"#{}\" aria-label=\"Permalink\">#</a></{}>\n",
+            id, level
+        ).into()));
+
+        insert(&mut toc, TocEntry {
+            level: level as u8,
+            text: text.trim().to_string(),
+            id,
+            children: Vec::new(),
+        });
+    }
+
+    (output, toc)
+}
+
+fn unique_id(used: &mut HashSet<String>, text: &str) -> String {
+    let mut base = slugify(text);
+    if base.is_empty() {
+        base = "section".to_string();
+    }
+
+    let mut id = base.clone();
+    let mut n = 1;
+    while used.contains(&id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    used.insert(id.clone());
+    id
+}
+
+// Nests an entry under the last entry of a higher level, if there is one
+fn insert(entries: &mut Vec<TocEntry>, entry: TocEntry) {
+    match entries.last_mut() {
+        Some(last) if last.level < entry.level => insert(&mut last.children, entry),
+        _ => entries.push(entry),
+    }
+}
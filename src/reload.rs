@@ -0,0 +1,165 @@
+//! Development mode file watching.
+//!
+//! Watches the content and templates directories and applies changes
+//! incrementally: an edited post or page is re-parsed into the [`PostStore`]
+//! and an edited `.hbs` file is re-registered with Handlebars. Parse and template
+//! errors are logged and the previous version keeps being served.
+
+use handlebars::Handlebars;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+
+use crate::config::SiteConfig;
+use crate::models::{is_source, BlogPost, Page, PostStore, PAGES_DIR};
+
+/// Starts watching `content_dir` and `templates_dir`.
+///
+/// Posts and pages are re-rendered with the Markdown options in `config`.
+/// Changes are applied on the current actix runtime. The returned watcher
+/// must be kept alive for as long as reloading should happen.
+pub fn watch(
+    content_dir: impl Into<PathBuf>,
+    templates_dir: impl Into<PathBuf>,
+    store: Arc<RwLock<PostStore>>,
+    handlebars: Arc<RwLock<Handlebars<'static>>>,
+    config: Arc<SiteConfig>,
+) -> notify::Result<RecommendedWatcher> {
+    let content = WatchedDir::new(content_dir.into())?;
+    let templates = WatchedDir::new(templates_dir.into())?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        match res {
+            Ok(event) => {
+                // The receiver only goes away when the server shuts down
+                let _ = tx.send(event);
+            }
+            Err(e) => log::error!("File watcher error: {}", e),
+        }
+    })?;
+    watcher.watch(&content.canonical, RecursiveMode::Recursive)?;
+    watcher.watch(&templates.canonical, RecursiveMode::Recursive)?;
+
+    log::info!(
+        "Watching {:?} and {:?} for changes",
+        content.dir,
+        templates.dir
+    );
+
+    let pages_dir = content.dir.join(PAGES_DIR);
+    actix_web::rt::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if !(event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove()) {
+                continue;
+            }
+
+            for path in event.paths {
+                if let Some(path) = content.relative(&path, "md") {
+                    // Only files that loading at startup would have picked up
+                    if path.starts_with(&pages_dir) {
+                        if is_source(&pages_dir, &path) {
+                            reload_page(&store, &pages_dir, &config, path).await;
+                        }
+                    } else if is_source(&content.dir, &path) {
+                        reload_post(&store, &content.dir, &config, path).await;
+                    }
+                } else if let Some(path) = templates.relative(&path, "hbs") {
+                    reload_template(&handlebars, &templates.dir, &path);
+                }
+            }
+        }
+    });
+
+    Ok(watcher)
+}
+
+// A watched directory, remembered both as configured and canonicalized
+// (notify reports absolute paths)
+struct WatchedDir {
+    dir: PathBuf,
+    canonical: PathBuf,
+}
+
+impl WatchedDir {
+    fn new(dir: PathBuf) -> notify::Result<Self> {
+        let canonical = dir.canonicalize()?;
+        Ok(Self { dir, canonical })
+    }
+
+    // Maps a reported path back under the configured directory so it matches
+    // the paths the files were originally loaded from
+    fn relative(&self, path: &Path, extension: &str) -> Option<PathBuf> {
+        if path.extension().and_then(|s| s.to_str()) != Some(extension) {
+            return None;
+        }
+        let rest = path.strip_prefix(&self.canonical).ok()?;
+        Some(self.dir.join(rest))
+    }
+}
+
+async fn reload_post(store: &RwLock<PostStore>, content_dir: &Path, config: &SiteConfig, path: PathBuf) {
+    if !path.exists() {
+        let removed = store.write().expect("post store lock poisoned").remove(&path);
+        if let Some(post) = removed {
+            log::info!("Removed blog post {:?} ({})", path, post.slug);
+        }
+        return;
+    }
+
+    match BlogPost::from_file(&path, content_dir, config).await {
+        Ok(post) => {
+            log::info!("Reloaded blog post {:?} ({})", path, post.slug);
+            store.write().expect("post store lock poisoned").insert(post);
+        }
+        Err(e) => log::error!("Failed to reload blog post {:?}: {}", path, e),
+    }
+}
+
+async fn reload_page(store: &RwLock<PostStore>, pages_dir: &Path, config: &SiteConfig, path: PathBuf) {
+    if !path.exists() {
+        let removed = store.write().expect("post store lock poisoned").remove_page(&path);
+        if let Some(page) = removed {
+            log::info!("Removed page {:?} ({})", path, page.slug);
+        }
+        return;
+    }
+
+    match Page::from_file(&path, pages_dir, config).await {
+        Ok(page) => {
+            log::info!("Reloaded page {:?} ({})", path, page.slug);
+            store.write().expect("post store lock poisoned").insert_page(page);
+        }
+        Err(e) => log::error!("Failed to reload page {:?}: {}", path, e),
+    }
+}
+
+fn reload_template(handlebars: &RwLock<Handlebars<'static>>, templates_dir: &Path, path: &Path) {
+    let Some(name) = template_name(templates_dir, path) else {
+        return;
+    };
+    let mut handlebars = handlebars.write().expect("handlebars lock poisoned");
+
+    if !path.exists() {
+        handlebars.unregister_template(&name);
+        log::info!("Unregistered template {}", name);
+        return;
+    }
+
+    match handlebars.register_template_file(&name, path) {
+        Ok(()) => log::info!("Reloaded template {}", name),
+        Err(e) => log::error!("Failed to reload template {}: {}", name, e),
+    }
+}
+
+// Template names mirror `register_templates_directory`: the path relative to
+// the templates directory, without extension, using `/` separators
+fn template_name(templates_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(templates_dir).ok()?.with_extension("");
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    Some(parts.join("/"))
+}
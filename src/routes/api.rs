@@ -0,0 +1,125 @@
+use actix_web::error::InternalError;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::{Arc, RwLock};
+
+use crate::config::SiteConfig;
+use crate::models::{BlogPost, PostStore};
+
+const DEFAULT_PER_PAGE: usize = 10;
+const MAX_PER_PAGE: usize = 100;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api")
+            // Malformed query strings get a JSON error like everything else
+            .app_data(web::QueryConfig::default().error_handler(|err, _| {
+                let response = json_error(StatusCode::BAD_REQUEST, &err.to_string());
+                InternalError::from_response(err, response).into()
+            }))
+            .service(web::resource("/posts").route(web::get().to(list_posts)))
+            .service(web::resource("/posts/{slug}").route(web::get().to(get_post)))
+            .default_service(web::to(|| async {
+                json_error(StatusCode::NOT_FOUND, "Not found")
+            })),
+    );
+}
+
+/// Which body a post is returned with.
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum ContentFormat {
+    /// The rendered `html_content`
+    #[default]
+    Html,
+    /// The raw Markdown `content`
+    Markdown,
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    page: Option<usize>,
+    per_page: Option<usize>,
+    #[serde(default)]
+    content: ContentFormat,
+}
+
+#[derive(Deserialize)]
+struct PostQuery {
+    #[serde(default)]
+    content: ContentFormat,
+}
+
+async fn list_posts(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    query: web::Query<ListQuery>,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    let posts = store.posts(config.visibility());
+
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE);
+    if page == 0 || per_page == 0 || per_page > MAX_PER_PAGE {
+        let message = format!("page must be at least 1 and per_page between 1 and {}", MAX_PER_PAGE);
+        return Ok(json_error(StatusCode::BAD_REQUEST, &message));
+    }
+
+    let total = posts.len();
+    let total_pages = total.div_ceil(per_page).max(1);
+    if page > total_pages {
+        return Ok(json_error(StatusCode::NOT_FOUND, "Page not found"));
+    }
+
+    let items: Vec<Value> = posts
+        .iter()
+        .skip((page - 1) * per_page)
+        .take(per_page)
+        .map(|post| serialize_post(post, query.content))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "posts": items,
+        "page": page,
+        "per_page": per_page,
+        "total": total,
+        "total_pages": total_pages,
+    })))
+}
+
+async fn get_post(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+    query: web::Query<PostQuery>,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+
+    match store.get(&path, config.visibility()) {
+        Some(post) => Ok(HttpResponse::Ok().json(serialize_post(post, query.content))),
+        None => Ok(json_error(StatusCode::NOT_FOUND, "Post not found")),
+    }
+}
+
+// Serializes a post with only the requested body
+fn serialize_post(post: &BlogPost, content: ContentFormat) -> Value {
+    let mut value = serde_json::to_value(post).expect("posts always serialize");
+    if let Some(fields) = value.as_object_mut() {
+        match content {
+            ContentFormat::Html => fields.remove("content"),
+            ContentFormat::Markdown => fields.remove("html_content"),
+        };
+    }
+    value
+}
+
+fn json_error(status: StatusCode, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(json!({
+        "error": {
+            "status": status.as_u16(),
+            "message": message,
+        }
+    }))
+}
@@ -0,0 +1,37 @@
+use actix_web::{web, HttpResponse, Result};
+use handlebars::Handlebars;
+use serde_json::json;
+use std::sync::{Arc, RwLock};
+
+use crate::config::SiteConfig;
+use crate::models::PostStore;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/authors/{id}").route(web::get().to(author)));
+}
+
+// An author's profile and everything they wrote
+async fn author(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let Some(author) = config.author(&path) else {
+        return Ok(HttpResponse::NotFound().body("Author not found"));
+    };
+    let store = store.read().expect("post store lock poisoned");
+    
+    let data = json!({
+        "title": author.name,
+        "description": if author.bio.is_empty() { format!("Posts by {}", author.name) } else { author.bio.clone() },
+        "author": author,
+        "posts": store.author_posts(&author.id, config.visibility()),
+    });
+    
+    let body = super::render(&hb, &config, &store, "author", data);
+    
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(body))
+}
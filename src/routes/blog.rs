@@ -1,33 +1,80 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::http::header::LastModified;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
 use handlebars::Handlebars;
+use chrono::Utc;
+use serde::Deserialize;
 use serde_json::json;
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
 
-use crate::models::BlogPost;
+use super::pagination::Pagination;
+use crate::config::SiteConfig;
+use crate::models::{PostStatus, PostStore, Visibility};
+use crate::slug::slugify;
+
+// How many related posts are listed under a post
+const RELATED_POSTS: usize = 3;
 
 // No longer using the configure function since we're calling these handlers directly now
 // from the main routes module
 
-pub async fn archive(hb: web::Data<Arc<Handlebars<'_>>>) -> Result<HttpResponse> {
-    let posts = match BlogPost::get_all_posts().await {
-        Ok(posts) => posts,
-        Err(e) => {
-            log::error!("Failed to get blog posts: {}", e);
-            return Ok(HttpResponse::InternalServerError().body("Failed to load blog posts"));
+#[derive(Deserialize)]
+pub struct PostQuery {
+    /// Signed token unlocking a draft or scheduled post
+    preview: Option<String>,
+}
+
+impl PostQuery {
+    /// A valid preview token shows this one post (and its assets) even if it
+    /// isn't live yet.
+    fn visibility(&self, config: &SiteConfig, slug: &str) -> Visibility {
+        match self.preview.as_deref() {
+            Some(token) if config.verify_preview_token(slug, token) => Visibility::Preview,
+            _ => config.visibility(),
         }
+    }
+}
+
+pub async fn archive(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+) -> Result<HttpResponse> {
+    render_archive(&hb, &store, &config, 1)
+}
+
+pub async fn archive_page(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<usize>,
+) -> Result<HttpResponse> {
+    render_archive(&hb, &store, &config, path.into_inner())
+}
+
+fn render_archive(
+    hb: &RwLock<Handlebars<'_>>,
+    store: &RwLock<PostStore>,
+    config: &SiteConfig,
+    page: usize,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    let posts = store.posts(config.visibility());
+    
+    let Some(pagination) = Pagination::new("/archive", page, config.archive_page_size, posts.len()) else {
+        return Ok(HttpResponse::NotFound().body("Page not found"));
     };
     
     let data = json!({
         "title": "Archive",
         "description": "Archive of all blog posts",
-        "posts": posts,
+        "posts": &posts[pagination.range.clone()],
+        "pagination": pagination,
         "isArchive": true,
     });
     
-    let body = hb.render("blog/archive", &data).unwrap_or_else(|err| {
-        log::error!("Template rendering error: {}", err);
-        "Error rendering template".to_string()
-    });
+    let body = super::render(hb, config, &store, "blog/archive", data);
     
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
@@ -35,39 +82,74 @@ pub async fn archive(hb: web::Data<Arc<Handlebars<'_>>>) -> Result<HttpResponse>
 }
 
 pub async fn post(
-    hb: web::Data<Arc<Handlebars<'_>>>,
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
     path: web::Path<String>,
+    query: web::Query<PostQuery>,
 ) -> Result<HttpResponse> {
     let slug = path.into_inner();
+    let store = store.read().expect("post store lock poisoned");
     
-    let posts = match BlogPost::get_all_posts().await {
-        Ok(posts) => posts,
-        Err(e) => {
-            log::error!("Failed to get blog posts: {}", e);
-            return Ok(HttpResponse::InternalServerError().body("Failed to load blog posts"));
-        }
-    };
-    
-    // Find the post with the matching slug
-    let post = posts.into_iter().find(|p| p.slug == slug);
-    
-    match post {
+    match store.get(&slug, query.visibility(&config, &slug)) {
         Some(post) => {
+            let status = post.status(Utc::now());
+            let (prev, next) = store.neighbours(post, config.visibility());
+            let related = store.related(post, config.visibility(), RELATED_POSTS);
+            let series = post
+                .series
+                .as_deref()
+                .and_then(|name| store.series(&slugify(name), config.visibility()))
+                .map(|series| json!({
+                    "name": series.name,
+                    "slug": series.slug,
+                    "part": series.part(post),
+                    "total": series.posts.len(),
+                    "posts": series.posts,
+                }));
             let data = json!({
                 "title": post.title,
                 "description": post.description,
                 "post": post,
+                "prev": prev,
+                "next": next,
+                "related": related,
+                "series": series,
+                "status": status,
+                "isDraft": status == PostStatus::Draft,
+                "isScheduled": status == PostStatus::Scheduled,
             });
             
-            let body = hb.render("blog/post", &data).unwrap_or_else(|err| {
-                log::error!("Template rendering error: {}", err);
-                "Error rendering template".to_string()
-            });
+            let body = super::render(&hb, &config, &store, "blog/post", data);
+            
+            let last_modified = SystemTime::from(post.last_modified());
             
             Ok(HttpResponse::Ok()
                 .content_type("text/html; charset=utf-8")
+                .insert_header(LastModified(last_modified.into()))
                 .body(body))
         },
         None => Ok(HttpResponse::NotFound().body("Post not found")),
     }
+}
+
+/// Serves a file that sits next to a page bundle's `index.md`.
+pub async fn asset(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<(String, String)>,
+    query: web::Query<PostQuery>,
+) -> Result<HttpResponse> {
+    let (slug, file) = path.into_inner();
+    
+    let dir = {
+        let store = store.read().expect("post store lock poisoned");
+        store
+            .get(&slug, query.visibility(&config, &slug))
+            .and_then(|post| post.bundle_dir())
+            .map(Path::to_path_buf)
+    };
+    
+    super::bundle_asset(&req, dir, &file).await
 }
\ No newline at end of file
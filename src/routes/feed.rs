@@ -0,0 +1,376 @@
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+
+use crate::config::SiteConfig;
+use crate::models::{BlogPost, PostStore, Taxonomy};
+
+// How many of the latest posts a feed carries
+const FEED_LIMIT: usize = 20;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/feed.xml").route(web::get().to(rss)));
+    cfg.service(web::resource("/atom.xml").route(web::get().to(atom)));
+    cfg.service(web::resource("/feed.json").route(web::get().to(json_feed)));
+    cfg.service(web::resource("/tags/{tag}/feed.xml").route(web::get().to(tag_rss)));
+    cfg.service(web::resource("/tags/{tag}/atom.xml").route(web::get().to(tag_atom)));
+    cfg.service(web::resource("/tags/{tag}/feed.json").route(web::get().to(tag_json_feed)));
+    cfg.service(web::resource("/categories/{category}/feed.xml").route(web::get().to(category_rss)));
+    cfg.service(web::resource("/categories/{category}/atom.xml").route(web::get().to(category_atom)));
+    cfg.service(web::resource("/categories/{category}/feed.json").route(web::get().to(category_json_feed)));
+}
+
+#[derive(Clone, Copy)]
+enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    fn file(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "feed.xml",
+            FeedFormat::Atom => "atom.xml",
+            FeedFormat::Json => "feed.json",
+        }
+    }
+
+    fn response(self, config: &SiteConfig, feed: &Feed) -> HttpResponse {
+        let (content_type, body) = match self {
+            FeedFormat::Rss => ("application/rss+xml; charset=utf-8", render_rss(config, feed)),
+            FeedFormat::Atom => ("application/atom+xml; charset=utf-8", render_atom(config, feed)),
+            FeedFormat::Json => ("application/feed+json; charset=utf-8", render_json(config, feed)),
+        };
+        HttpResponse::Ok().content_type(content_type).body(body)
+    }
+}
+
+/// What a feed covers: the whole site or a single tag or category.
+struct Feed<'a> {
+    title: String,
+    description: String,
+    /// Site-relative path of the HTML page the feed mirrors
+    page: String,
+    /// Site-relative path of the feed itself
+    path: String,
+    posts: Vec<&'a BlogPost>,
+}
+
+impl<'a> Feed<'a> {
+    fn site(config: &SiteConfig, store: &'a PostStore, format: FeedFormat) -> Self {
+        let mut posts = store.posts(config.visibility());
+        posts.truncate(FEED_LIMIT);
+
+        Feed {
+            title: config.title.clone(),
+            description: config.description.clone(),
+            page: "/".to_string(),
+            path: format!("/{}", format.file()),
+            posts,
+        }
+    }
+
+    fn term(
+        config: &SiteConfig,
+        store: &'a PostStore,
+        taxonomy: Taxonomy,
+        slug: &str,
+        format: FeedFormat,
+    ) -> Option<Self> {
+        let (term, mut posts) = store.term(taxonomy, slug, config.visibility())?;
+        posts.truncate(FEED_LIMIT);
+
+        let page = format!("/{}/{}", taxonomy.name(), term.slug);
+        Some(Feed {
+            title: format!("{} - {}", config.title, term.name),
+            description: format!("Posts filed under {}", term.name),
+            path: format!("{}/{}", page, format.file()),
+            page,
+            posts,
+        })
+    }
+
+    // The most recent change to any post in the feed
+    fn updated(&self) -> Option<DateTime<Utc>> {
+        self.posts.iter().map(|post| post.last_modified()).max()
+    }
+}
+
+async fn rss(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    let feed = Feed::site(&config, &store, FeedFormat::Rss);
+    Ok(FeedFormat::Rss.response(&config, &feed))
+}
+
+async fn atom(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    let feed = Feed::site(&config, &store, FeedFormat::Atom);
+    Ok(FeedFormat::Atom.response(&config, &feed))
+}
+
+async fn json_feed(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    let feed = Feed::site(&config, &store, FeedFormat::Json);
+    Ok(FeedFormat::Json.response(&config, &feed))
+}
+
+async fn tag_rss(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    term_feed(&store, &config, Taxonomy::Tags, &path, FeedFormat::Rss)
+}
+
+async fn tag_atom(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    term_feed(&store, &config, Taxonomy::Tags, &path, FeedFormat::Atom)
+}
+
+async fn tag_json_feed(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    term_feed(&store, &config, Taxonomy::Tags, &path, FeedFormat::Json)
+}
+
+async fn category_rss(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    term_feed(&store, &config, Taxonomy::Categories, &path, FeedFormat::Rss)
+}
+
+async fn category_atom(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    term_feed(&store, &config, Taxonomy::Categories, &path, FeedFormat::Atom)
+}
+
+async fn category_json_feed(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    term_feed(&store, &config, Taxonomy::Categories, &path, FeedFormat::Json)
+}
+
+fn term_feed(
+    store: &RwLock<PostStore>,
+    config: &SiteConfig,
+    taxonomy: Taxonomy,
+    slug: &str,
+    format: FeedFormat,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+
+    match Feed::term(config, &store, taxonomy, slug, format) {
+        Some(feed) => Ok(format.response(config, &feed)),
+        None => Ok(HttpResponse::NotFound().body(format!("{} not found", taxonomy.singular()))),
+    }
+}
+
+// RSS 2.0, with dates in RFC 822 format
+fn render_rss(config: &SiteConfig, feed: &Feed) -> String {
+    let mut xml = String::new();
+
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+    xml.push_str("<channel>\n");
+    let _ = writeln!(xml, "<title>{}</title>", escape(&feed.title));
+    let _ = writeln!(xml, "<link>{}</link>", escape(&config.url(&feed.page)));
+    let _ = writeln!(xml, "<description>{}</description>", escape(&feed.description));
+    let _ = writeln!(
+        xml,
+        "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>",
+        escape(&config.url(&feed.path))
+    );
+    if let Some(updated) = feed.updated() {
+        let _ = writeln!(xml, "<lastBuildDate>{}</lastBuildDate>", updated.to_rfc2822());
+    }
+
+    for post in &feed.posts {
+        let link = escape(&config.url(&format!("/post/{}", post.slug)));
+
+        xml.push_str("<item>\n");
+        let _ = writeln!(xml, "<title>{}</title>", escape(&post.title));
+        let _ = writeln!(xml, "<link>{}</link>", link);
+        let _ = writeln!(xml, "<guid isPermaLink=\"true\">{}</guid>", link);
+        let _ = writeln!(xml, "<pubDate>{}</pubDate>", post.date.to_rfc2822());
+        // RSS's own <author> wants an email address, so names go in dc:creator
+        for author in &post.authors {
+            let _ = writeln!(xml, "<dc:creator>{}</dc:creator>", escape(&author.name));
+        }
+        let _ = writeln!(xml, "<description>{}</description>", escape(&post.description));
+        if config.feed_full_content {
+            let _ = writeln!(xml, "<content:encoded>{}</content:encoded>", escape(&post.html_content));
+        }
+        for category in post.categories.iter().chain(&post.tags) {
+            let _ = writeln!(xml, "<category>{}</category>", escape(category));
+        }
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n");
+    xml.push_str("</rss>\n");
+    xml
+}
+
+// Atom 1.0, with dates in RFC 3339 format
+fn render_atom(config: &SiteConfig, feed: &Feed) -> String {
+    let mut xml = String::new();
+    let updated = feed
+        .updated()
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    let _ = writeln!(xml, "<title>{}</title>", escape(&feed.title));
+    let _ = writeln!(xml, "<subtitle>{}</subtitle>", escape(&feed.description));
+    let _ = writeln!(
+        xml,
+        "<link href=\"{}\" rel=\"self\" type=\"application/atom+xml\"/>",
+        escape(&config.url(&feed.path))
+    );
+    let _ = writeln!(
+        xml,
+        "<link href=\"{}\" rel=\"alternate\" type=\"text/html\"/>",
+        escape(&config.url(&feed.page))
+    );
+    let _ = writeln!(xml, "<id>{}</id>", escape(&config.url(&feed.path)));
+    let _ = writeln!(xml, "<updated>{}</updated>", updated);
+    let _ = writeln!(xml, "<author><name>{}</name></author>", escape(&config.author));
+
+    for post in &feed.posts {
+        let link = escape(&config.url(&format!("/post/{}", post.slug)));
+        let date = post.date.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let updated = post.last_modified().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        xml.push_str("<entry>\n");
+        let _ = writeln!(xml, "<title>{}</title>", escape(&post.title));
+        let _ = writeln!(xml, "<link href=\"{}\" rel=\"alternate\" type=\"text/html\"/>", link);
+        let _ = writeln!(xml, "<id>{}</id>", link);
+        let _ = writeln!(xml, "<published>{}</published>", date);
+        let _ = writeln!(xml, "<updated>{}</updated>", updated);
+        for author in &post.authors {
+            let _ = writeln!(
+                xml,
+                "<author><name>{}</name><uri>{}</uri></author>",
+                escape(&author.name),
+                escape(&config.url(&format!("/authors/{}", author.id)))
+            );
+        }
+        let _ = writeln!(xml, "<summary>{}</summary>", escape(&post.description));
+        if config.feed_full_content {
+            // xml:base lets readers resolve the post's relative links
+            let _ = writeln!(
+                xml,
+                "<content type=\"html\" xml:base=\"{}\">{}</content>",
+                link,
+                escape(&post.html_content)
+            );
+        }
+        for category in post.categories.iter().chain(&post.tags) {
+            let _ = writeln!(xml, "<category term=\"{}\"/>", escape(category));
+        }
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+// JSON Feed 1.1 (https://jsonfeed.org/version/1.1)
+fn render_json(config: &SiteConfig, feed: &Feed) -> String {
+    let items: Vec<Value> = feed
+        .posts
+        .iter()
+        .map(|post| {
+            let url = config.url(&format!("/post/{}", post.slug));
+            let mut item = json!({
+                "id": url,
+                "url": url,
+                "title": post.title,
+                "summary": post.description,
+                "date_published": post.date.to_rfc3339_opts(SecondsFormat::Secs, true),
+                "tags": post.categories.iter().chain(&post.tags).collect::<Vec<_>>(),
+            });
+            if !post.authors.is_empty() {
+                let authors: Vec<Value> = post
+                    .authors
+                    .iter()
+                    .map(|author| {
+                        let mut value = json!({
+                            "name": author.name,
+                            "url": config.url(&format!("/authors/{}", author.id)),
+                        });
+                        if let Some(avatar) = &author.avatar {
+                            let absolute = avatar.contains("://");
+                            value["avatar"] = json!(if absolute { avatar.clone() } else { config.url(avatar) });
+                        }
+                        value
+                    })
+                    .collect();
+                item["authors"] = json!(authors);
+            }
+            if post.updated.is_some() {
+                item["date_modified"] = json!(post.last_modified().to_rfc3339_opts(SecondsFormat::Secs, true));
+            }
+            // Every item needs either content_html or content_text
+            if config.feed_full_content {
+                item["content_html"] = json!(post.html_content);
+            } else {
+                item["content_text"] = json!(post.description);
+            }
+            item
+        })
+        .collect();
+
+    let feed = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": feed.title,
+        "description": feed.description,
+        "home_page_url": config.url(&feed.page),
+        "feed_url": config.url(&feed.path),
+        "authors": [{ "name": config.author }],
+        "items": items,
+    });
+
+    serde_json::to_string_pretty(&feed).expect("JSON values always serialize")
+}
+
+/// Escapes text for use in XML content and attribute values.
+pub(super) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,39 +1,58 @@
 use actix_web::{web, HttpResponse, Result};
 use handlebars::Handlebars;
 use serde_json::json;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-use crate::models::BlogPost;
+use super::pagination::Pagination;
+use crate::config::SiteConfig;
+use crate::models::PostStore;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(web::resource("/").route(web::get().to(index)));
+    cfg.service(web::resource("/page/{n}").route(web::get().to(page)));
 }
 
-async fn index(hb: web::Data<Arc<Handlebars<'_>>>) -> Result<HttpResponse> {
-    let posts = match BlogPost::get_all_posts().await {
-        Ok(posts) => posts,
-        Err(e) => {
-            log::error!("Failed to get blog posts: {}", e);
-            return Ok(HttpResponse::InternalServerError().body("Failed to load blog posts"));
-        }
-    };
+async fn index(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+) -> Result<HttpResponse> {
+    render_page(&hb, &store, &config, 1)
+}
+
+async fn page(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<usize>,
+) -> Result<HttpResponse> {
+    render_page(&hb, &store, &config, path.into_inner())
+}
+
+fn render_page(
+    hb: &RwLock<Handlebars<'_>>,
+    store: &RwLock<PostStore>,
+    config: &SiteConfig,
+    page: usize,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    let posts = store.posts(config.visibility());
     
-    // Take only the latest few posts for the home page
-    let latest_posts = posts.into_iter().take(5).collect::<Vec<_>>();
+    let Some(pagination) = Pagination::new("/", page, config.home_page_size, posts.len()) else {
+        return Ok(HttpResponse::NotFound().body("Page not found"));
+    };
     
     let data = json!({
-        "title": "Blog",
-        "description": "Personal blog and website",
-        "posts": latest_posts,
+        "title": config.title,
+        "description": config.description,
+        "posts": &posts[pagination.range.clone()],
+        "pagination": pagination,
         "isHome": true,
     });
     
-    let body = hb.render("index", &data).unwrap_or_else(|err| {
-        log::error!("Template rendering error: {}", err);
-        "Error rendering template".to_string()
-    });
+    let body = super::render(hb, config, &store, "index", data);
     
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(body))
-}
\ No newline at end of file
+}
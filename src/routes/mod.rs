@@ -1,19 +1,110 @@
 mod home;
-mod about;
 mod blog;
+mod taxonomy;
+mod feed;
+mod api;
+mod sitemap;
+mod pagination;
+mod page;
+mod series;
+mod author;
 
-use actix_web::web;
+use actix_files::NamedFile;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use handlebars::Handlebars;
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::config::{MenuItem, SiteConfig};
+use crate::models::PostStore;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     // Home route - shows latest posts
     home::configure(cfg);
     
-    // About page
-    about::configure(cfg);
-    
     // Archive page
     cfg.service(web::resource("/archive").route(web::get().to(blog::archive)));
+    cfg.service(web::resource("/archive/page/{n}").route(web::get().to(blog::archive_page)));
     
     // Individual post pages
     cfg.service(web::resource("/post/{slug}").route(web::get().to(blog::post)));
-}
\ No newline at end of file
+    
+    // Assets of page bundles, e.g. /post/foo/image.png
+    cfg.service(web::resource("/post/{slug}/{file:.+}").route(web::get().to(blog::asset)));
+    
+    // Tag and category listings
+    taxonomy::configure(cfg);
+    
+    // Author profiles
+    author::configure(cfg);
+    
+    // Multi-part series, in reading order
+    series::configure(cfg);
+    
+    // RSS, Atom and JSON feeds
+    feed::configure(cfg);
+    
+    // Read-only JSON API
+    api::configure(cfg);
+    
+    // sitemap.xml and robots.txt for crawlers
+    sitemap::configure(cfg);
+    
+    // Standalone pages like /about; last, so they never shadow other routes
+    page::configure(cfg);
+}
+
+// Renders a template with the site config available as `site` and the
+// navigation as `menu`, logging failures and falling back to an error message
+fn render(
+    hb: &RwLock<Handlebars<'_>>,
+    config: &SiteConfig,
+    store: &PostStore,
+    template: &str,
+    mut data: Value,
+) -> String {
+    if let Some(fields) = data.as_object_mut() {
+        fields.insert("site".to_string(), json!(config));
+        fields.insert("menu".to_string(), json!(menu(config, store)));
+    }
+    
+    let hb = hb.read().expect("handlebars lock poisoned");
+    hb.render(template, &data).unwrap_or_else(|err| {
+        log::error!("Template rendering error: {}", err);
+        "Error rendering template".to_string()
+    })
+}
+
+// Serves `file` from the page bundle in `dir`: only plain relative paths,
+// never hidden files and never the Markdown sources
+async fn bundle_asset(req: &HttpRequest, dir: Option<PathBuf>, file: &str) -> Result<HttpResponse> {
+    let file = Path::new(file);
+    let is_safe = file.components().all(|c| match c {
+        Component::Normal(name) => !name.to_string_lossy().starts_with('.'),
+        _ => false,
+    });
+    let is_markdown = file
+        .extension()
+        .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case("md"));
+    
+    let Some(asset) = dir.filter(|_| is_safe && !is_markdown).map(|dir| dir.join(file)) else {
+        return Ok(HttpResponse::NotFound().body("File not found"));
+    };
+    match NamedFile::open_async(asset).await {
+        Ok(file) => Ok(file.into_response(req)),
+        Err(_) => Ok(HttpResponse::NotFound().body("File not found")),
+    }
+}
+
+// The configured links plus every page that asks to be listed, in order
+fn menu(config: &SiteConfig, store: &PostStore) -> Vec<MenuItem> {
+    let pages = store.pages().iter().filter(|page| page.menu).map(|page| MenuItem {
+        title: page.title.clone(),
+        url: format!("/{}", page.slug),
+        order: page.menu_order,
+    });
+    let mut menu: Vec<MenuItem> = config.menu.iter().cloned().chain(pages).collect();
+    menu.sort_by_key(|item| item.order);
+    menu
+}
@@ -0,0 +1,56 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use handlebars::Handlebars;
+use serde_json::json;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use crate::config::SiteConfig;
+use crate::models::PostStore;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/{slug}").route(web::get().to(page)));
+    // Assets of page bundles, e.g. /team/photo.jpg
+    cfg.service(web::resource("/{slug}/{file:.+}").route(web::get().to(asset)));
+}
+
+async fn page(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let slug = path.into_inner();
+    let store = store.read().expect("post store lock poisoned");
+    
+    match store.page(&slug) {
+        Some(page) => {
+            let data = json!({
+                "title": page.title,
+                "description": page.description,
+                "page": page,
+            });
+            
+            let body = super::render(&hb, &config, &store, "page", data);
+            
+            Ok(HttpResponse::Ok()
+                .content_type("text/html; charset=utf-8")
+                .body(body))
+        },
+        None => Ok(HttpResponse::NotFound().body("Page not found")),
+    }
+}
+
+async fn asset(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse> {
+    let (slug, file) = path.into_inner();
+    
+    let dir = {
+        let store = store.read().expect("post store lock poisoned");
+        store.page(&slug).and_then(|page| page.bundle_dir()).map(Path::to_path_buf)
+    };
+    
+    super::bundle_asset(&req, dir, &file).await
+}
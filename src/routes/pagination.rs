@@ -0,0 +1,49 @@
+use serde::Serialize;
+use std::ops::Range;
+
+/// Where a listing page sits among its siblings, as passed to templates.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Pagination {
+    /// 1-based number of this page
+    pub current: usize,
+    /// Number of pages; always at least 1, even with nothing to list
+    pub total: usize,
+    pub prev: Option<String>,
+    pub next: Option<String>,
+    /// Index range of the items on this page
+    #[serde(skip)]
+    pub range: Range<usize>,
+}
+
+impl Pagination {
+    /// Splits `items` into pages of `per_page`, returning `None` when `page`
+    /// is out of range.
+    ///
+    /// The first page lives at `base` and the rest at `{base}/page/{n}`.
+    pub fn new(base: &str, page: usize, per_page: usize, items: usize) -> Option<Self> {
+        let per_page = per_page.max(1);
+        let total = items.div_ceil(per_page).max(1);
+        if page == 0 || page > total {
+            return None;
+        }
+
+        let start = (page - 1) * per_page;
+        Some(Self {
+            current: page,
+            total,
+            prev: (page > 1).then(|| page_url(base, page - 1)),
+            next: (page < total).then(|| page_url(base, page + 1)),
+            range: start..(start + per_page).min(items),
+        })
+    }
+}
+
+/// The URL of page `n` of the listing at `base`.
+pub fn page_url(base: &str, n: usize) -> String {
+    let base = base.trim_end_matches('/');
+    match n {
+        1 if base.is_empty() => "/".to_string(),
+        1 => base.to_string(),
+        _ => format!("{}/page/{}", base, n),
+    }
+}
@@ -0,0 +1,38 @@
+use actix_web::{web, HttpResponse, Result};
+use handlebars::Handlebars;
+use serde_json::json;
+use std::sync::{Arc, RwLock};
+
+use crate::config::SiteConfig;
+use crate::models::PostStore;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/series/{name}").route(web::get().to(series)));
+}
+
+// Lists the parts of a series in reading order
+async fn series(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    
+    let Some(series) = store.series(&path, config.visibility()) else {
+        return Ok(HttpResponse::NotFound().body("Series not found"));
+    };
+    
+    let data = json!({
+        "title": format!("Series: {}", series.name),
+        "description": format!("All {} parts of {}", series.posts.len(), series.name),
+        "total": series.posts.len(),
+        "series": series,
+    });
+    
+    let body = super::render(&hb, &config, &store, "series", data);
+    
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(body))
+}
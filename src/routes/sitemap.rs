@@ -0,0 +1,92 @@
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+
+use super::feed::escape;
+use crate::config::SiteConfig;
+use crate::models::{PostStore, Taxonomy, Visibility};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/sitemap.xml").route(web::get().to(sitemap)));
+    cfg.service(web::resource("/robots.txt").route(web::get().to(robots)));
+}
+
+async fn sitemap(
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+
+    // Search engines should never see drafts or scheduled posts, even when
+    // the site runs in preview mode
+    let posts = store.posts(Visibility::Published);
+    let newest = posts.iter().map(|post| post.last_modified()).max();
+
+    let mut urls: Vec<(String, Option<DateTime<Utc>>)> = vec![
+        ("/".to_string(), newest),
+        ("/archive".to_string(), newest),
+    ];
+    urls.extend(store.pages().iter().map(|page| (format!("/{}", page.slug), None)));
+    urls.extend(posts.iter().map(|post| (format!("/post/{}", post.slug), Some(post.last_modified()))));
+
+    for taxonomy in [Taxonomy::Tags, Taxonomy::Categories] {
+        let terms = store.terms(taxonomy, Visibility::Published);
+        if terms.is_empty() {
+            continue;
+        }
+        urls.push((format!("/{}", taxonomy.name()), newest));
+        for term in terms {
+            let lastmod = store
+                .term(taxonomy, &term.slug, Visibility::Published)
+                .and_then(|(_, posts)| posts.iter().map(|post| post.last_modified()).max());
+            urls.push((format!("/{}/{}", taxonomy.name(), term.slug), lastmod));
+        }
+    }
+
+    for author in &config.authors {
+        let lastmod = store
+            .author_posts(&author.id, Visibility::Published)
+            .iter()
+            .map(|post| post.last_modified())
+            .max();
+        urls.push((format!("/authors/{}", author.id), lastmod));
+    }
+    for series in store.all_series(Visibility::Published) {
+        let lastmod = series.posts.iter().map(|post| post.last_modified()).max();
+        urls.push((format!("/series/{}", series.slug), lastmod));
+    }
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for (path, lastmod) in urls {
+        xml.push_str("<url>\n");
+        let _ = writeln!(xml, "<loc>{}</loc>", escape(&config.url(&path)));
+        if let Some(lastmod) = lastmod {
+            let _ = writeln!(xml, "<lastmod>{}</lastmod>", lastmod.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        xml.push_str("</url>\n");
+    }
+    xml.push_str("</urlset>\n");
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/xml; charset=utf-8")
+        .body(xml))
+}
+
+async fn robots(config: web::Data<Arc<SiteConfig>>) -> Result<HttpResponse> {
+    let mut body = String::from("User-agent: *\n");
+    if config.robots_disallow.is_empty() {
+        // An empty Disallow allows everything
+        body.push_str("Disallow:\n");
+    }
+    for path in &config.robots_disallow {
+        let _ = writeln!(body, "Disallow: {}", path);
+    }
+    let _ = writeln!(body, "\nSitemap: {}", config.url("/sitemap.xml"));
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(body))
+}
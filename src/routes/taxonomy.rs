@@ -0,0 +1,107 @@
+use actix_web::{web, HttpResponse, Result};
+use handlebars::Handlebars;
+use serde_json::json;
+use std::sync::{Arc, RwLock};
+
+use crate::config::SiteConfig;
+use crate::models::{PostStore, Taxonomy};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/tags").route(web::get().to(tags)));
+    cfg.service(web::resource("/tags/{tag}").route(web::get().to(tag)));
+    cfg.service(web::resource("/categories").route(web::get().to(categories)));
+    cfg.service(web::resource("/categories/{category}").route(web::get().to(category)));
+}
+
+async fn tags(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+) -> Result<HttpResponse> {
+    list(&hb, &store, &config, Taxonomy::Tags)
+}
+
+async fn tag(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    term(&hb, &store, &config, Taxonomy::Tags, &path.into_inner())
+}
+
+async fn categories(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+) -> Result<HttpResponse> {
+    list(&hb, &store, &config, Taxonomy::Categories)
+}
+
+async fn category(
+    hb: web::Data<Arc<RwLock<Handlebars<'_>>>>,
+    store: web::Data<Arc<RwLock<PostStore>>>,
+    config: web::Data<Arc<SiteConfig>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    term(&hb, &store, &config, Taxonomy::Categories, &path.into_inner())
+}
+
+// Lists every term in a taxonomy with its post count
+fn list(
+    hb: &RwLock<Handlebars<'_>>,
+    store: &RwLock<PostStore>,
+    config: &SiteConfig,
+    taxonomy: Taxonomy,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    
+    let data = json!({
+        "title": taxonomy.plural(),
+        "description": format!("All {} used on this blog", taxonomy.name()),
+        "taxonomy": taxonomy.name(),
+        "label": taxonomy.plural(),
+        "terms": store.terms(taxonomy, config.visibility()),
+    });
+    
+    let body = super::render(hb, config, &store, "taxonomy/list", data);
+    
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(body))
+}
+
+// Lists the posts filed under a single term
+fn term(
+    hb: &RwLock<Handlebars<'_>>,
+    store: &RwLock<PostStore>,
+    config: &SiteConfig,
+    taxonomy: Taxonomy,
+    slug: &str,
+) -> Result<HttpResponse> {
+    let store = store.read().expect("post store lock poisoned");
+    
+    match store.term(taxonomy, slug, config.visibility()) {
+        Some((term, posts)) => {
+            let data = json!({
+                "title": format!("{}: {}", taxonomy.singular(), term.name),
+                "description": format!("Posts filed under {}", term.name),
+                "taxonomy": taxonomy.name(),
+                "label": taxonomy.singular(),
+                "posts": posts,
+                "feed": {
+                    "title": term.name,
+                    "path": format!("/{}/{}", taxonomy.name(), term.slug),
+                },
+                "term": term,
+            });
+            
+            let body = super::render(hb, config, &store, "taxonomy/term", data);
+            
+            Ok(HttpResponse::Ok()
+                .content_type("text/html; charset=utf-8")
+                .body(body))
+        },
+        None => Ok(HttpResponse::NotFound().body(format!("{} not found", taxonomy.singular()))),
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::io::Write;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::config::SiteConfig;
+use crate::models::{BlogPost, BlogPostError};
+use crate::slug::slugify;
+
+#[derive(Error, Debug)]
+pub enum ScaffoldError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to read existing posts: {0}")]
+    Posts(#[from] BlogPostError),
+
+    #[error("Cannot make a slug out of {0:?}")]
+    EmptySlug(String),
+
+    #[error("{0:?} already exists")]
+    FileExists(PathBuf),
+
+    #[error("Slug {slug:?} is already used by {path:?}")]
+    SlugTaken { slug: String, path: PathBuf },
+}
+
+/// Creates `<slug>.md` in `config.content_dir` for a new post titled `title`,
+/// with the slug made from the title and the date set to `now`.
+///
+/// Never overwrites a file, and refuses slugs another post already uses.
+pub async fn new_post(
+    config: &SiteConfig,
+    title: &str,
+    now: DateTime<Utc>,
+) -> Result<PathBuf, ScaffoldError> {
+    let content_dir = config.content_dir.as_path();
+    let slug = slugify(title);
+    if slug.is_empty() {
+        return Err(ScaffoldError::EmptySlug(title.to_string()));
+    }
+
+    let path = content_dir.join(format!("{}.md", slug));
+    if path.exists() {
+        return Err(ScaffoldError::FileExists(path));
+    }
+    if content_dir.is_dir()
+        && let Some(post) = BlogPost::load_dir(content_dir, config).await?.into_iter().find(|post| post.slug == slug)
+    {
+        return Err(ScaffoldError::SlugTaken { slug, path: post.source });
+    }
+
+    // JSON strings are valid YAML, which keeps titles with colons or quotes intact
+    let content = format!(
+        "---\ntitle: {}\ndate: {}\ndescription: \"\"\nslug: {}\n---\n\n",
+        serde_json::to_string(title).expect("strings always serialize"),
+        now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        slug,
+    );
+
+    std::fs::create_dir_all(content_dir)?;
+    // create_new guards against a file appearing since the check above
+    let mut file = std::fs::OpenOptions::new().write(true).create_new(true).open(&path)?;
+    file.write_all(content.as_bytes())?;
+
+    Ok(path)
+}
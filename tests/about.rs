@@ -1,19 +1,25 @@
 use actix_web::{test, App, web};
-use andy::routes;
+use andy::{config::SiteConfig, routes, models::PostStore};
 use handlebars::Handlebars;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[actix_web::test]
 async fn test_about_route() {
     // Initialize handlebars with templates
     let mut handlebars = Handlebars::new();
     handlebars.register_templates_directory(".hbs", "templates").expect("Failed to register templates");
-    let handlebars_ref = Arc::new(handlebars);
+    let handlebars_ref = Arc::new(RwLock::new(handlebars));
+
+    // The About page lives in content/pages
+    let post_store = PostStore::load("content", &SiteConfig::default()).await.expect("Failed to load content");
+    let post_store_ref = Arc::new(RwLock::new(post_store));
 
     // Create test app
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(handlebars_ref))
+            .app_data(web::Data::new(post_store_ref))
+            .app_data(web::Data::new(Arc::new(SiteConfig::default())))
             .configure(routes::configure)
     ).await;
     
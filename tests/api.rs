@@ -0,0 +1,88 @@
+pub mod common;
+
+use common::TestSite;
+
+// Five published posts and a draft
+fn site() -> TestSite {
+    let mut site = TestSite::new();
+    for day in 1..=5 {
+        site = site.post(&format!("post-{day}"), &format!("2024-01-0{day}T12:00:00Z"), "tags: [api]\n");
+    }
+    site.post("secret", "2024-02-01T12:00:00Z", "tags: [api]\ndraft: true\n")
+}
+
+#[actix_web::test]
+async fn test_json_feed() {
+    let site = site();
+
+    let resp = site.get("/feed.json").await;
+    assert_eq!(resp.status, 200);
+    assert_eq!(resp.header("content-type"), Some("application/feed+json; charset=utf-8"));
+    let feed = resp.json();
+    assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+    assert_eq!(feed["feed_url"], "http://localhost:8080/feed.json");
+    let items = feed["items"].as_array().unwrap();
+    assert_eq!(items.len(), 5);
+    assert_eq!(items[0]["url"], "http://localhost:8080/post/post-5");
+    assert_eq!(items[0]["date_published"], "2024-01-05T12:00:00Z");
+    assert!(items[0]["content_html"].as_str().unwrap().contains("<em>post-5</em>"));
+
+    let resp = site.get("/tags/api/feed.json").await;
+    assert_eq!(resp.status, 200);
+    assert_eq!(resp.json()["items"].as_array().unwrap().len(), 5);
+}
+
+#[actix_web::test]
+async fn test_list_posts_paginated() {
+    let site = site();
+
+    let resp = site.get("/api/posts?page=2&per_page=2").await;
+    assert_eq!(resp.status, 200);
+    assert_eq!(resp.header("content-type"), Some("application/json"));
+    let body = resp.json();
+    assert_eq!(body["page"], 2);
+    assert_eq!(body["total"], 5);
+    assert_eq!(body["total_pages"], 3);
+    let slugs: Vec<_> = body["posts"].as_array().unwrap().iter().map(|p| p["slug"].as_str().unwrap()).collect();
+    assert_eq!(slugs, ["post-3", "post-2"]);
+
+    let resp = site.get("/api/posts?page=4&per_page=2").await;
+    assert_eq!(resp.status, 404);
+    assert_eq!(resp.json()["error"]["status"], 404);
+
+    let resp = site.get("/api/posts?page=abc").await;
+    assert_eq!(resp.status, 400);
+    assert_eq!(resp.json()["error"]["status"], 400);
+}
+
+#[actix_web::test]
+async fn test_get_post_content_formats() {
+    let site = site();
+
+    let resp = site.get("/api/posts/post-1").await;
+    assert_eq!(resp.status, 200);
+    let post = resp.json();
+    assert_eq!(post["title"], "Post post-1");
+    assert!(post["html_content"].as_str().unwrap().contains("<em>post-1</em>"));
+    assert!(post.get("content").is_none());
+
+    let post = site.get("/api/posts/post-1?content=markdown").await.json();
+    assert!(post["content"].as_str().unwrap().contains("*post-1*"));
+    assert!(post.get("html_content").is_none());
+}
+
+#[actix_web::test]
+async fn test_api_errors_are_json() {
+    let site = site();
+
+    let resp = site.get("/api/posts/missing").await;
+    assert_eq!(resp.status, 404);
+    assert_eq!(resp.json()["error"]["message"], "Post not found");
+
+    // Drafts are not exposed either
+    assert_eq!(site.get("/api/posts/secret").await.status, 404);
+
+    let resp = site.get("/api/nothing-here").await;
+    assert_eq!(resp.status, 404);
+    assert_eq!(resp.json()["error"]["status"], 404);
+}
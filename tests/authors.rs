@@ -0,0 +1,141 @@
+pub mod common;
+
+use actix_web::http::StatusCode;
+use andy::{config::{ConfigError, SiteConfig}, models::BlogPostError};
+use common::TestSite;
+use std::fs;
+use std::path::Path;
+
+const AUTHORS: &str = r#"
+[sam]
+name = "Sam Writer"
+avatar = "/static/sam.png"
+
+[andy]
+name = "Andy"
+bio = "Writes about Rust."
+links = [{ title = "GitHub", url = "https://github.example/andy" }]
+"#;
+
+// A config whose authors file lives in `dir`
+fn config(dir: &Path) -> SiteConfig {
+    fs::write(dir.join("authors.toml"), AUTHORS).unwrap();
+    fs::write(
+        dir.join("config.toml"),
+        format!("authors_file = {:?}\n", dir.join("authors.toml")),
+    )
+    .unwrap();
+    SiteConfig::load(dir.join("config.toml")).expect("Failed to load config")
+}
+
+// A site with the authors file
+fn authors_site() -> TestSite {
+    let site = TestSite::new();
+    let config = config(site.path());
+    site.with_config(config)
+}
+
+fn site() -> TestSite {
+    authors_site()
+        .post("solo", "2024-01-01T12:00:00Z", "author: andy\n")
+        .post("together", "2024-02-01T12:00:00Z", "authors: [sam, andy]\n")
+        .post("anonymous", "2024-03-01T12:00:00Z", "")
+}
+
+#[actix_web::test]
+async fn test_load_authors_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(dir.path());
+
+    let ids: Vec<_> = config.authors.iter().map(|author| author.id.as_str()).collect();
+    assert_eq!(ids, ["andy", "sam"]);
+    let andy = config.author("andy").unwrap();
+    assert_eq!(andy.bio, "Writes about Rust.");
+    assert_eq!(andy.links[0].title, "GitHub");
+    assert_eq!(config.author("sam").unwrap().avatar.as_deref(), Some("/static/sam.png"));
+
+    // No authors file means no authors
+    fs::write(dir.path().join("other.toml"), format!("authors_file = {:?}\n", dir.path().join("nope.toml"))).unwrap();
+    let missing = SiteConfig::load(dir.path().join("other.toml")).unwrap();
+    assert!(missing.authors.is_empty());
+
+    fs::write(dir.path().join("authors.toml"), "[andy]\nname = \"Andy\"\nemail = \"a@example.com\"\n").unwrap();
+    assert!(matches!(SiteConfig::load(dir.path().join("config.toml")), Err(ConfigError::Parse { .. })));
+}
+
+#[actix_web::test]
+async fn test_post_authors_are_resolved() {
+    let site = authors_site()
+        .post("both", "2024-01-01T12:00:00Z", "author: andy\nauthors: [sam]\n")
+        .post("twice", "2024-01-01T12:00:00Z", "author: sam\nauthors: [andy, sam, andy]\n")
+        .post("ghost", "2024-01-01T12:00:00Z", "author: ghost\n");
+
+    let post = site.parse("both.md").await.expect("Failed to parse post");
+    let names: Vec<_> = post.authors.iter().map(|author| author.name.as_str()).collect();
+    assert_eq!(names, ["Andy", "Sam Writer"]);
+
+    // Repeated ids are listed once, in order of first appearance
+    let post = site.parse("twice.md").await.expect("Failed to parse post");
+    let names: Vec<_> = post.authors.iter().map(|author| author.name.as_str()).collect();
+    assert_eq!(names, ["Sam Writer", "Andy"]);
+
+    match site.parse("ghost.md").await {
+        Err(BlogPostError::UnknownAuthor(id)) => assert_eq!(id, "ghost"),
+        other => panic!("Expected UnknownAuthor, got {:?}", other),
+    }
+
+    let problems = site.check().await;
+    assert_eq!(problems.len(), 1, "{:?}", problems);
+    assert!(problems[0].message.contains("Unknown author \"ghost\""));
+}
+
+#[actix_web::test]
+async fn test_author_page() {
+    let site = site();
+
+    let body = site.body("/authors/andy").await;
+    assert!(body.contains("<h1>Andy</h1>"));
+    assert!(body.contains("Writes about Rust."));
+    assert!(body.contains("<a href=\"https://github.example/andy\" rel=\"me\">GitHub</a>"));
+    assert!(body.contains("/post/solo"));
+    assert!(body.contains("/post/together"));
+    assert!(!body.contains("/post/anonymous"));
+
+    let body = site.body("/authors/sam").await;
+    assert!(body.contains("<img src=\"/static/sam.png\""));
+    assert!(!body.contains("/post/solo"));
+
+    assert_eq!(site.get("/authors/ghost").await.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_post_bylines() {
+    let site = site();
+
+    let body = site.body("/post/together").await;
+    assert!(body.contains(
+        "By <a href=\"/authors/sam\" rel=\"author\">Sam Writer</a> and <a href=\"/authors/andy\" rel=\"author\">Andy</a>"
+    ));
+
+    let body = site.body("/post/anonymous").await;
+    assert!(!body.contains("post-authors"));
+}
+
+#[actix_web::test]
+async fn test_feeds_credit_authors() {
+    let site = site();
+
+    let rss = site.body("/feed.xml").await;
+    assert!(rss.contains("<dc:creator>Sam Writer</dc:creator>\n<dc:creator>Andy</dc:creator>"));
+
+    let atom = site.body("/atom.xml").await;
+    assert!(atom.contains("<author><name>Andy</name><uri>http://localhost:8080/authors/andy</uri></author>"));
+
+    let feed = site.get("/feed.json").await.json();
+    let together = feed["items"].as_array().unwrap().iter().find(|item| item["title"] == "Post together").unwrap();
+    assert_eq!(together["authors"][0]["name"], "Sam Writer");
+    assert_eq!(together["authors"][0]["avatar"], "http://localhost:8080/static/sam.png");
+    assert_eq!(together["authors"][1]["url"], "http://localhost:8080/authors/andy");
+    let anonymous = feed["items"].as_array().unwrap().iter().find(|item| item["title"] == "Post anonymous").unwrap();
+    assert!(anonymous.get("authors").is_none());
+}
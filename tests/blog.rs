@@ -1,9 +1,11 @@
+pub mod common;
+
 use actix_web::{test, App, web};
-use andy::routes;
-use handlebars::Handlebars;
+use andy::{config::SiteConfig, routes, models::PostStore};
+use common::TestSite;
 use std::fs;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[actix_web::test]
 async fn test_archive_route() {
@@ -15,7 +17,7 @@ async fn test_archive_route() {
 
     // Create a test post if none exists
     let test_post_path = content_dir.join("test-archive.md");
-    if !fs::metadata(&test_post_path).is_ok() {
+    if fs::metadata(&test_post_path).is_err() {
         let test_post_content = r#"---
 title: Test Archive Post
 date: 2024-03-01T12:00:00Z
@@ -30,51 +32,19 @@ This is a test post for testing the archive route.
         fs::write(&test_post_path, test_post_content).unwrap();
     }
 
-    // Initialize handlebars
-    let mut handlebars = Handlebars::new();
-    
-    // Register formatDate helper
-    handlebars.register_helper("formatDate", Box::new(|
-        h: &handlebars::Helper,
-        _: &handlebars::Handlebars,
-        _: &handlebars::Context,
-        _: &mut handlebars::RenderContext,
-        out: &mut dyn handlebars::Output,
-    | -> handlebars::HelperResult {
-        let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
-        
-        if let Ok(date) = chrono::DateTime::parse_from_rfc3339(param) {
-            let utc_date = date.with_timezone(&chrono::Utc);
-            let formatted = utc_date.format("%B %e, %Y").to_string();
-            out.write(&formatted)?;
-        } else {
-            out.write(param)?;
-        }
-        
-        Ok(())
-    }));
-    
-    // Register currentYear helper
-    handlebars.register_helper("currentYear", Box::new(|
-        _: &handlebars::Helper,
-        _: &handlebars::Handlebars,
-        _: &handlebars::Context,
-        _: &mut handlebars::RenderContext,
-        out: &mut dyn handlebars::Output,
-    | -> handlebars::HelperResult {
-        let current_year = chrono::Utc::now().format("%Y").to_string();
-        out.write(&current_year)?;
-        Ok(())
-    }));
-
-    // Register templates
-    handlebars.register_templates_directory(".hbs", "templates").expect("Failed to register templates");
-    let handlebars_ref = Arc::new(handlebars);
+    // Initialize handlebars with helpers and templates
+    let handlebars_ref = Arc::new(RwLock::new(common::handlebars()));
+
+    // Load posts into the in-memory store
+    let post_store = PostStore::load("content", &SiteConfig::default()).await.expect("Failed to load blog posts");
+    let post_store_ref = Arc::new(RwLock::new(post_store));
 
     // Create test app
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(handlebars_ref))
+            .app_data(web::Data::new(post_store_ref))
+            .app_data(web::Data::new(Arc::new(SiteConfig::default())))
             .service(actix_files::Files::new("/static", "static"))
             .configure(routes::configure)
     ).await;
@@ -106,7 +76,7 @@ async fn test_post_route() {
 
     // Create a test post if none exists
     let test_post_path = content_dir.join("test-post.md");
-    if !fs::metadata(&test_post_path).is_ok() {
+    if fs::metadata(&test_post_path).is_err() {
         let test_post_content = r#"---
 title: Test Post
 date: 2024-03-01T12:00:00Z
@@ -121,51 +91,19 @@ This is a test post for testing the post route.
         fs::write(&test_post_path, test_post_content).unwrap();
     }
 
-    // Initialize handlebars
-    let mut handlebars = Handlebars::new();
-    
-    // Register formatDate helper
-    handlebars.register_helper("formatDate", Box::new(|
-        h: &handlebars::Helper,
-        _: &handlebars::Handlebars,
-        _: &handlebars::Context,
-        _: &mut handlebars::RenderContext,
-        out: &mut dyn handlebars::Output,
-    | -> handlebars::HelperResult {
-        let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
-        
-        if let Ok(date) = chrono::DateTime::parse_from_rfc3339(param) {
-            let utc_date = date.with_timezone(&chrono::Utc);
-            let formatted = utc_date.format("%B %e, %Y").to_string();
-            out.write(&formatted)?;
-        } else {
-            out.write(param)?;
-        }
-        
-        Ok(())
-    }));
-    
-    // Register currentYear helper
-    handlebars.register_helper("currentYear", Box::new(|
-        _: &handlebars::Helper,
-        _: &handlebars::Handlebars,
-        _: &handlebars::Context,
-        _: &mut handlebars::RenderContext,
-        out: &mut dyn handlebars::Output,
-    | -> handlebars::HelperResult {
-        let current_year = chrono::Utc::now().format("%Y").to_string();
-        out.write(&current_year)?;
-        Ok(())
-    }));
-
-    // Register templates
-    handlebars.register_templates_directory(".hbs", "templates").expect("Failed to register templates");
-    let handlebars_ref = Arc::new(handlebars);
+    // Initialize handlebars with helpers and templates
+    let handlebars_ref = Arc::new(RwLock::new(common::handlebars()));
+
+    // Load posts into the in-memory store
+    let post_store = PostStore::load("content", &SiteConfig::default()).await.expect("Failed to load blog posts");
+    let post_store_ref = Arc::new(RwLock::new(post_store));
 
     // Create test app
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(handlebars_ref))
+            .app_data(web::Data::new(post_store_ref))
+            .app_data(web::Data::new(Arc::new(SiteConfig::default())))
             .service(actix_files::Files::new("/static", "static"))
             .configure(routes::configure)
     ).await;
@@ -185,4 +123,146 @@ This is a test post for testing the post route.
     assert!(body_str.contains("Test Post"));
     assert!(body_str.contains("Test Post Content"));
     assert!(body_str.contains("March  1, 2024"));
-}
\ No newline at end of file
+}
+
+// Renders a post body with the given extra frontmatter lines
+async fn render_post(frontmatter: &str, markdown: &str, config: &SiteConfig) -> String {
+    let site = TestSite::new()
+        .with_config(config.clone())
+        .file("extensions.md", &common::post("extensions", "2024-03-01T12:00:00Z", frontmatter, &format!("\n{}", markdown)));
+    site.parse("extensions.md").await.expect("Failed to parse post").html_content
+}
+
+#[actix_web::test]
+async fn test_tables() {
+    let html = render_post("", "| Name | Value |\n|------|-------|\n| a    | 1     |\n", &SiteConfig::default()).await;
+    assert!(html.contains("<table>"));
+    assert!(html.contains("<th>Name</th>"));
+    assert!(html.contains("<td>1</td>"));
+}
+
+#[actix_web::test]
+async fn test_footnotes() {
+    let html = render_post("", "Claim.[^1]\n\n[^1]: Source.\n", &SiteConfig::default()).await;
+    assert!(html.contains("<sup class=\"footnote-reference\"><a href=\"#1\">1</a></sup>"));
+    assert!(html.contains("<div class=\"footnote-definition\" id=\"1\">"));
+}
+
+#[actix_web::test]
+async fn test_strikethrough() {
+    let html = render_post("", "This is ~~gone~~.\n", &SiteConfig::default()).await;
+    assert!(html.contains("<del>gone</del>"));
+}
+
+#[actix_web::test]
+async fn test_tasklists() {
+    let html = render_post("", "- [x] Done\n- [ ] Todo\n", &SiteConfig::default()).await;
+    assert!(html.contains("<input disabled=\"\" type=\"checkbox\" checked=\"\"/>"));
+    assert!(html.contains("<input disabled=\"\" type=\"checkbox\"/>"));
+}
+
+#[actix_web::test]
+async fn test_smart_punctuation() {
+    let html = render_post("", "\"Quoted\" -- and --- then...\n", &SiteConfig::default()).await;
+    assert!(html.contains("“Quoted”"));
+    assert!(html.contains("–"));
+    assert!(html.contains("—"));
+    assert!(html.contains("…"));
+}
+
+#[actix_web::test]
+async fn test_extensions_disabled_for_site() {
+    let mut config = SiteConfig::default();
+    config.markdown.tables = false;
+    config.markdown.strikethrough = false;
+
+    let html = render_post("", "| a | b |\n|---|---|\n| 1 | 2 |\n\n~~kept~~\n", &config).await;
+    assert!(!html.contains("<table>"));
+    assert!(!html.contains("<del>"));
+    assert!(html.contains("~~kept~~"));
+}
+
+#[actix_web::test]
+async fn test_extensions_overridden_per_post() {
+    let mut config = SiteConfig::default();
+    config.markdown.strikethrough = false;
+
+    // The post turns strikethrough back on and smart punctuation off
+    let frontmatter = "markdown:\n  strikethrough: true\n  smart_punctuation: false\n";
+    let html = render_post(frontmatter, "~~gone~~ \"plain\"\n", &config).await;
+    assert!(html.contains("<del>gone</del>"));
+    assert!(html.contains("&quot;plain&quot;"));
+
+    // Unknown extensions are rejected rather than silently ignored
+    let site = TestSite::new()
+        .with_config(config)
+        .post("typo", "2024-03-01T12:00:00Z", "markdown:\n  tabels: true\n");
+    assert!(site.parse("typo.md").await.is_err());
+}
+
+#[actix_web::test]
+async fn test_heading_ids_and_toc() {
+    let markdown = "# Intro\n\n## Setup\n\n### Install `cargo`\n\n## Setup\n\n# Wrap up!\n";
+    let site = TestSite::new().file("toc.md", &common::post("toc", "2024-03-01T12:00:00Z", "toc: true\n", &format!("\n{}", markdown)));
+    let post = site.parse("toc.md").await.expect("Failed to parse post");
+
+    assert!(post.show_toc);
+    assert!(post.html_content.contains("<h1 id=\"intro\">Intro<a class=\"heading-anchor\" href=\"#intro\""));
+    assert!(post.html_content.contains("<h3 id=\"install-cargo\">Install <code>cargo</code>"));
+
+    // The second "Setup" gets a suffix instead of a clashing id
+    assert!(post.html_content.contains("<h2 id=\"setup\">"));
+    assert!(post.html_content.contains("<h2 id=\"setup-1\">"));
+
+    let toc = &post.toc;
+    assert_eq!(toc.len(), 2);
+    assert_eq!((toc[0].level, toc[0].text.as_str(), toc[0].id.as_str()), (1, "Intro", "intro"));
+    let ids: Vec<_> = toc[0].children.iter().map(|entry| entry.id.as_str()).collect();
+    assert_eq!(ids, ["setup", "setup-1"]);
+    assert_eq!(toc[0].children[0].children[0].text, "Install cargo");
+    assert_eq!(toc[1].id, "wrap-up");
+    assert!(toc[1].children.is_empty());
+}
+
+#[actix_web::test]
+async fn test_toc_shown_only_when_enabled() {
+    let body = "## First\n\n### Nested\n\n## Second\n";
+    let site = TestSite::new()
+        .file(
+            "with-toc.md",
+            &format!("---\ntitle: With Toc\ndate: 2024-03-01T12:00:00Z\ndescription: Toc\ntoc: true\n---\n\n{}", body),
+        )
+        .file(
+            "without-toc.md",
+            &format!("---\ntitle: Without Toc\ndate: 2024-03-02T12:00:00Z\ndescription: No toc\n---\n\n{}", body),
+        );
+
+    let body = site.body("/post/with-toc").await;
+    assert!(body.contains("class=\"post-toc\""));
+    assert!(body.contains("<a href=\"#first\">First</a>"));
+    assert!(body.contains("<a href=\"#nested\">Nested</a>"));
+    assert!(body.contains("<a href=\"#second\">Second</a>"));
+
+    let body = site.body("/post/without-toc").await;
+    assert!(!body.contains("class=\"post-toc\""));
+    assert!(body.contains("<h2 id=\"first\">"));
+}
+
+#[actix_web::test]
+async fn test_post_links_to_neighbours_and_related_posts() {
+    let site = TestSite::new()
+        .post("first", "2024-01-01T12:00:00Z", "tags: [rust]\n")
+        .post("second", "2024-02-01T12:00:00Z", "tags: [rust]\n")
+        .post("third", "2024-03-01T12:00:00Z", "tags: [travel]\n");
+
+    let body = site.body("/post/second").await;
+    assert!(body.contains("<a href=\"/post/first\" class=\"post-pager-previous\" rel=\"prev\">← Post first</a>"));
+    assert!(body.contains("<a href=\"/post/third\" class=\"post-pager-next\" rel=\"next\">Post third →</a>"));
+    assert!(body.contains("Related Posts"));
+    assert!(body.contains("<a href=\"/post/first\">Post first</a>"));
+
+    // The newest post has no next post
+    let body = site.body("/post/third").await;
+    assert!(body.contains("class=\"post-pager-previous\""));
+    assert!(!body.contains("class=\"post-pager-next\""));
+}
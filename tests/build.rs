@@ -0,0 +1,84 @@
+pub mod common;
+
+use andy::{build, config::SiteConfig};
+use common::TestSite;
+use std::fs;
+use std::path::Path;
+
+#[actix_web::test]
+async fn test_build_matches_server_output() {
+    let static_dir = tempfile::tempdir().unwrap();
+    common::write(&static_dir.path().join("css/style.css"), "body {}");
+
+    let site = TestSite::new()
+        .with_config(SiteConfig {
+            static_dir: static_dir.path().to_path_buf(),
+            home_page_size: 2,
+            ..SiteConfig::default()
+        })
+        .post("first", "2024-01-01T12:00:00Z", "tags: [rust]\n")
+        .post("second", "2024-02-01T12:00:00Z", "categories: [notes]\n")
+        .post("third", "2024-03-01T12:00:00Z", "")
+        .post("draft", "2024-04-01T12:00:00Z", "draft: true\n")
+        .file("pages/about.md", "---\ntitle: About\nmenu: true\n---\n\nHi\n");
+
+    let out = tempfile::tempdir().unwrap();
+    site.build(out.path()).await;
+
+    let pages = [
+        ("/", "index.html"),
+        ("/page/2", "page/2/index.html"),
+        ("/about", "about/index.html"),
+        ("/archive", "archive/index.html"),
+        ("/post/first", "post/first/index.html"),
+        ("/tags/rust", "tags/rust/index.html"),
+        ("/tags/rust/feed.xml", "tags/rust/feed.xml"),
+        ("/categories/notes", "categories/notes/index.html"),
+        ("/feed.xml", "feed.xml"),
+        ("/atom.xml", "atom.xml"),
+        ("/feed.json", "feed.json"),
+        ("/sitemap.xml", "sitemap.xml"),
+        ("/robots.txt", "robots.txt"),
+    ];
+    for (uri, file) in pages {
+        let written = fs::read_to_string(out.path().join(file)).unwrap_or_else(|_| panic!("{} was not written", file));
+        assert_eq!(written, site.body(uri).await, "{} differs from {}", file, uri);
+    }
+
+    assert_eq!(fs::read_to_string(out.path().join("static/css/style.css")).unwrap(), "body {}");
+    assert!(!out.path().join("page/3").exists());
+    assert!(!out.path().join("post/draft").exists());
+}
+
+#[actix_web::test]
+async fn test_output_paths() {
+    let out = Path::new("public");
+    assert_eq!(build::output_path(out, "/"), out.join("index.html"));
+    assert_eq!(build::output_path(out, "/post/hello"), out.join("post/hello/index.html"));
+    assert_eq!(build::output_path(out, "/tags/rust/feed.xml"), out.join("tags/rust/feed.xml"));
+    assert_eq!(build::output_path(out, "/sitemap.xml"), out.join("sitemap.xml"));
+
+    // Dots in slugs don't make a file, and encoded slugs are decoded
+    assert_eq!(build::output_path(out, "/post/v1.2-release"), out.join("post/v1.2-release/index.html"));
+    assert_eq!(build::output_path(out, "/tags/caf%C3%A9"), out.join("tags/café/index.html"));
+}
+
+#[actix_web::test]
+async fn test_build_non_ascii_and_dotted_slugs() {
+    let site = TestSite::new()
+        .post("café", "2024-01-01T12:00:00Z", "tags: [Über]\n")
+        .post("v1.2-release", "2024-02-01T12:00:00Z", "");
+
+    let out = tempfile::tempdir().unwrap();
+    site.build(out.path()).await;
+
+    let pages = [
+        ("/post/caf%C3%A9", "post/café/index.html"),
+        ("/post/v1.2-release", "post/v1.2-release/index.html"),
+        ("/tags/%C3%BCber", "tags/über/index.html"),
+    ];
+    for (uri, file) in pages {
+        let written = fs::read_to_string(out.path().join(file)).unwrap_or_else(|_| panic!("{} was not written", file));
+        assert_eq!(written, site.body(uri).await, "{} differs from {}", file, uri);
+    }
+}
@@ -0,0 +1,120 @@
+pub mod common;
+
+use actix_web::http::StatusCode;
+use andy::models::Visibility;
+use common::TestSite;
+use std::fs;
+
+// A flat post, a nested post and a page bundle with an image
+fn site() -> TestSite {
+    TestSite::new()
+        .file("flat.md", "---\ntitle: Flat\ndate: 2024-01-01\ndescription: Flat\n---\n\nFlat\n")
+        .file("2024/nested.md", "---\ntitle: Nested\ndate: 2024-02-01\ndescription: Nested\n---\n\nNested\n")
+        .file(
+            "2024-03-01-my-trip/index.md",
+            "---\ntitle: My trip\ndescription: Photos\n---\n\n![The view](view.png)\n\n[Notes](./notes.txt), [flat](/post/flat) and [elsewhere](https://example.com/a.png)\n",
+        )
+        .file("2024-03-01-my-trip/view.png", "not really a png")
+        .file("2024-03-01-my-trip/notes.txt", "notes")
+        .file("2024-03-01-my-trip/draft-notes.md", "Not a post")
+        .file("2024-03-01-my-trip/Outline.MD", "Not a post either")
+        .file("2024-03-01-my-trip/.env", "SECRET=1")
+        .file("2024-03-01-my-trip/.git/config", "[core]")
+}
+
+#[actix_web::test]
+async fn test_nested_posts_and_bundles_are_found() {
+    let site = site();
+    let store = site.store().await;
+
+    let slugs: Vec<_> = store.all().iter().map(|p| p.slug.as_str()).collect();
+    assert_eq!(slugs, ["my-trip", "nested", "flat"]);
+
+    let trip = store.get("my-trip", Visibility::Published).unwrap();
+    assert_eq!(trip.date.to_rfc3339(), "2024-03-01T00:00:00+00:00");
+    assert_eq!(trip.bundle_dir(), Some(site.path().join("2024-03-01-my-trip").as_path()));
+    assert!(trip.html_content.contains(r#"<img src="/post/my-trip/view.png" alt="The view" />"#));
+    assert!(trip.html_content.contains(r#"href="/post/my-trip/notes.txt""#));
+    assert!(trip.html_content.contains(r#"href="/post/flat""#));
+    assert!(trip.html_content.contains(r#"href="https://example.com/a.png""#));
+
+    assert!(store.get("nested", Visibility::Published).unwrap().bundle_dir().is_none());
+}
+
+#[actix_web::test]
+async fn test_bundle_assets_are_served() {
+    let site = site();
+
+    let resp = site.get("/post/my-trip/view.png").await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.header("content-type"), Some("image/png"));
+    assert_eq!(resp.body, "not really a png");
+
+    for uri in [
+        "/post/my-trip/index.md",
+        "/post/my-trip/draft-notes.md",
+        "/post/my-trip/Outline.MD",
+        "/post/my-trip/.env",
+        "/post/my-trip/.git/config",
+        "/post/my-trip/missing.png",
+        "/post/my-trip/..%2F2024%2Fnested.md",
+        "/post/nested/view.png",
+        "/post/missing/view.png",
+    ] {
+        assert_eq!(site.get(uri).await.status, StatusCode::NOT_FOUND, "{}", uri);
+    }
+}
+
+#[actix_web::test]
+async fn test_build_copies_bundle_assets() {
+    let site = site();
+
+    let out = tempfile::tempdir().unwrap();
+    site.build(out.path()).await;
+
+    let post = out.path().join("post/my-trip");
+    assert!(post.join("index.html").exists());
+    assert_eq!(fs::read_to_string(post.join("view.png")).unwrap(), "not really a png");
+    assert!(!post.join("draft-notes.md").exists());
+    assert!(!post.join("Outline.MD").exists());
+    assert!(!post.join(".env").exists());
+}
+
+#[actix_web::test]
+async fn test_top_level_index_is_not_a_bundle() {
+    let site = site().file("index.md", "---\ntitle: Welcome\ndate: 2024-04-01\ndescription: Hi\n---\n\n![Logo](logo.png)\n");
+    let store = site.store().await;
+
+    let slugs: Vec<_> = store.all().iter().map(|p| p.slug.as_str()).collect();
+    assert_eq!(slugs, ["index", "my-trip", "nested", "flat"]);
+
+    let welcome = store.get("index", Visibility::Published).unwrap();
+    assert!(welcome.bundle_dir().is_none());
+    assert!(welcome.html_content.contains(r#"<img src="logo.png" alt="Logo" />"#));
+
+    assert_eq!(site.get("/post/index/flat.md").await.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_renamed_bundle_links_to_its_own_assets() {
+    let mut site = TestSite::new();
+    for (name, date) in [("first", "2024-01-01"), ("second", "2024-02-01")] {
+        site = site.file(
+            &format!("{name}/index.md"),
+            &format!("---\ntitle: {name}\ndate: {date}\ndescription: Photos\nslug: same\n---\n\n![](img.png)\n<!-- more -->\nRest\n"),
+        );
+    }
+    let mut store = site.store().await;
+
+    let second = store.get("same-2", Visibility::Published).unwrap();
+    assert!(second.html_content.contains(r#"<img src="/post/same-2/img.png""#));
+    assert!(second.excerpt.contains(r#"<img src="/post/same-2/img.png""#));
+    let first = store.get("same", Visibility::Published).unwrap();
+    assert!(first.html_content.contains(r#"<img src="/post/same/img.png""#));
+
+    // Once the slug is free again the links follow the post back to it
+    store.remove(&site.path().join("first/index.md"));
+    let second = store.get("same", Visibility::Published).unwrap();
+    assert_eq!(second.title, "second");
+    assert!(second.html_content.contains(r#"<img src="/post/same/img.png""#));
+}
@@ -0,0 +1,39 @@
+pub mod common;
+
+use common::TestSite;
+
+const DATE: &str = "2024-01-01T00:00:00Z";
+
+#[actix_web::test]
+async fn test_clean_content_has_no_problems() {
+    let site = TestSite::new()
+        .file("a.md", &common::post("a", DATE, "", "\n```rust\nfn main() {}\n```\n\nSee [b](/post/b#intro).\n"))
+        .file("b.md", &common::post("b", DATE, "", "\nBack to [a](/post/a).\n"));
+
+    let problems = site.check().await;
+    assert!(problems.is_empty(), "{:?}", problems);
+}
+
+#[actix_web::test]
+async fn test_reports_every_problem() {
+    let site = TestSite::new()
+        .file("bad-yaml.md", "---\ntitle: Bad\ndate: [oops\n---\n")
+        .file("bad-date.md", "---\ntitle: Bad\ndate: yesterday\ndescription: x\nslug: bad-date\n---\n")
+        .file("no-title.md", "---\ndate: 2024-01-01\n---\n")
+        .file("one.md", &common::post("same", DATE, "", "\nText\n"))
+        .file("two.md", &common::post("same", DATE, "", "\nIntro\n\n```klingon\nqapla\n```\n\nA [dead link](/post/missing) here.\n"));
+
+    let problems = site.check().await;
+    let lines: Vec<String> = problems
+        .iter()
+        .map(|p| p.to_string().replace(&format!("{}/", site.path().display()), ""))
+        .collect();
+
+    assert_eq!(lines.len(), 6, "{:#?}", lines);
+    assert!(lines[0].starts_with("bad-date.md:3: Failed to parse YAML frontmatter"));
+    assert!(lines[1].starts_with("bad-yaml.md:3: Failed to parse YAML frontmatter"));
+    assert_eq!(lines[2], "no-title.md: Missing required field: title");
+    assert!(lines[3].starts_with("two.md: Duplicate slug \"same\", also used by"));
+    assert_eq!(lines[4], "two.md:10: Unknown code block language \"klingon\"");
+    assert_eq!(lines[5], "two.md:14: Broken link to /post/missing");
+}
@@ -0,0 +1,148 @@
+//! Fixtures shared by the integration tests: a content directory in a temp
+//! dir, and an app serving it the way `main` does.
+
+use actix_web::http::header::HeaderMap;
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use andy::{build, check::{self, Problem}, config::SiteConfig, helpers, models::{BlogPost, BlogPostError, PostStore}, routes};
+use handlebars::Handlebars;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// Handlebars with the site's helpers and everything in `templates/`.
+pub fn handlebars() -> Handlebars<'static> {
+    let mut handlebars = Handlebars::new();
+    helpers::register(&mut handlebars);
+    handlebars.register_templates_directory(".hbs", "templates").expect("Failed to register templates");
+    handlebars
+}
+
+/// Writes `content` to `path`, creating its directory first.
+pub fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// The source of a post titled "Post {slug}" and described as "About {slug}",
+/// with `extra` frontmatter lines and then `body`.
+pub fn post(slug: &str, date: &str, extra: &str, body: &str) -> String {
+    format!("---\ntitle: Post {slug}\ndate: {date}\ndescription: About {slug}\nslug: {slug}\n{extra}---\n{body}")
+}
+
+/// Writes `{slug}.md` to `dir`: a [`post`] with a body of "Body of *{slug}*".
+pub fn write_post(dir: &Path, slug: &str, date: &str, extra: &str) {
+    write(&dir.join(format!("{slug}.md")), &post(slug, date, extra, &format!("\nBody of *{slug}*\n")));
+}
+
+/// A content directory that is loaded and served afresh for every request.
+pub struct TestSite {
+    dir: tempfile::TempDir,
+    pub config: SiteConfig,
+}
+
+impl Default for TestSite {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestSite {
+    /// An empty content directory, served with the default config.
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SiteConfig { content_dir: dir.path().to_path_buf(), ..SiteConfig::default() };
+        Self { dir, config }
+    }
+
+    /// Serves the site with `config`, its `content_dir` pointing here.
+    pub fn with_config(mut self, config: SiteConfig) -> Self {
+        self.config = SiteConfig { content_dir: self.path().to_path_buf(), ..config };
+        self
+    }
+
+    /// Adds a post written by [`write_post`].
+    pub fn post(self, slug: &str, date: &str, extra: &str) -> Self {
+        write_post(self.path(), slug, date, extra);
+        self
+    }
+
+    /// Adds any file, at `name` relative to the content directory.
+    pub fn file(self, name: &str, content: &str) -> Self {
+        write(&self.path().join(name), content);
+        self
+    }
+
+    /// The content directory.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Parses the post at `name`, relative to the content directory.
+    pub async fn parse(&self, name: &str) -> Result<BlogPost, BlogPostError> {
+        BlogPost::from_file(self.path().join(name), self.path(), &self.config).await
+    }
+
+    /// Loads the content directory the way `main` does.
+    pub async fn store(&self) -> PostStore {
+        PostStore::load(self.path(), &self.config).await.expect("Failed to load posts")
+    }
+
+    /// Lints the content directory the way `andy check` does.
+    pub async fn check(&self) -> Vec<Problem> {
+        check::check(self.path(), &self.config).await.expect("Failed to check posts")
+    }
+
+    /// Exports the site to `out` the way `andy build` does.
+    pub async fn build(&self, out: &Path) {
+        build::build(
+            Arc::new(self.config.clone()),
+            Arc::new(RwLock::new(handlebars())),
+            Arc::new(RwLock::new(self.store().await)),
+            out,
+        )
+        .await
+        .expect("Build failed");
+    }
+
+    /// Serves a single GET request for `uri`.
+    pub async fn get(&self, uri: &str) -> Response {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Arc::new(RwLock::new(handlebars()))))
+                .app_data(web::Data::new(Arc::new(RwLock::new(self.store().await))))
+                .app_data(web::Data::new(Arc::new(self.config.clone())))
+                .configure(routes::configure)
+        ).await;
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let body = test::read_body(resp).await;
+        Response { status, headers, body: String::from_utf8(body.to_vec()).unwrap() }
+    }
+
+    /// Serves a GET request for `uri` that must return 200, returning the body.
+    pub async fn body(&self, uri: &str) -> String {
+        let resp = self.get(uri).await;
+        assert_eq!(resp.status, StatusCode::OK, "GET {}", uri);
+        resp.body
+    }
+}
+
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|value| value.to_str().unwrap())
+    }
+
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).expect("Body should be JSON")
+    }
+}
@@ -0,0 +1,79 @@
+pub mod common;
+
+use andy::config::{ConfigError, SiteConfig};
+use common::TestSite;
+use std::fs;
+use std::path::PathBuf;
+
+#[actix_web::test]
+async fn test_load_config_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, r#"
+bind = "0.0.0.0:3000"
+title = "Andy's Notes"
+content_dir = "posts"
+theme = "dark"
+home_page_size = 10
+robots_disallow = []
+
+[markdown]
+smart_punctuation = false
+"#).unwrap();
+
+    let config = SiteConfig::load(&path).expect("Failed to load config");
+    assert_eq!(config.bind, "0.0.0.0:3000");
+    assert_eq!(config.title, "Andy's Notes");
+    assert_eq!(config.content_dir, PathBuf::from("posts"));
+    assert_eq!(config.home_page_size, 10);
+    assert!(config.robots_disallow.is_empty());
+    assert!(!config.markdown.smart_punctuation);
+    assert!(config.markdown.tables);
+
+    // Anything not in the file keeps its default
+    assert_eq!(config.author, "Andy");
+    assert_eq!(config.archive_page_size, 50);
+
+    // The theme's directories replace the plain ones
+    assert_eq!(config.templates_path(), PathBuf::from("themes/dark/templates"));
+    assert_eq!(config.static_path(), PathBuf::from("themes/dark/static"));
+}
+
+#[actix_web::test]
+async fn test_missing_config_file_uses_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = SiteConfig::load(dir.path().join("config.toml")).expect("Failed to load config");
+    assert_eq!(config.content_dir, SiteConfig::default().content_dir);
+    assert_eq!(config.templates_path(), PathBuf::from("templates"));
+}
+
+#[actix_web::test]
+async fn test_invalid_config_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+
+    fs::write(&path, "home_page_size = \"lots\"\n").unwrap();
+    assert!(matches!(SiteConfig::load(&path), Err(ConfigError::Parse { .. })));
+
+    // Typos are reported rather than silently ignored
+    fs::write(&path, "titel = \"Blog\"\n").unwrap();
+    assert!(matches!(SiteConfig::load(&path), Err(ConfigError::Parse { .. })));
+}
+
+#[actix_web::test]
+async fn test_templates_see_site_config() {
+    let config = SiteConfig {
+        title: "Andy's Notes".to_string(),
+        description: "Notes on Rust".to_string(),
+        author: "Andy Example".to_string(),
+        preview_secret: Some("hunter2".to_string()),
+        ..SiteConfig::default()
+    };
+
+    let body = TestSite::new().with_config(config).body("/").await;
+
+    assert!(body.contains("<title>Andy&#x27;s Notes</title>"));
+    assert!(body.contains("Notes on Rust"));
+    assert!(body.contains("Andy Example"));
+    assert!(!body.contains("hunter2"));
+}
@@ -0,0 +1,114 @@
+pub mod common;
+
+use andy::{config::SiteConfig, models::{PostStatus, Visibility}};
+use chrono::Duration;
+use common::TestSite;
+
+fn site() -> TestSite {
+    TestSite::new()
+        .post("live", "2024-01-01T12:00:00Z", "")
+        .post("unfinished", "2024-02-01T12:00:00Z", "draft: true\n")
+        .post("upcoming", "2999-01-01T12:00:00Z", "")
+}
+
+#[actix_web::test]
+async fn test_post_status() {
+    let store = site().store().await;
+    let now = chrono::Utc::now();
+
+    let status = |slug| store.all().iter().find(|p| p.slug == slug).unwrap().status(now);
+    assert_eq!(status("live"), PostStatus::Published);
+    assert_eq!(status("unfinished"), PostStatus::Draft);
+    assert_eq!(status("upcoming"), PostStatus::Scheduled);
+
+    // Scheduled posts publish themselves once their date passes
+    let upcoming = store.all().iter().find(|p| p.slug == "upcoming").unwrap();
+    assert_eq!(upcoming.status(upcoming.date + Duration::seconds(1)), PostStatus::Published);
+}
+
+#[actix_web::test]
+async fn test_store_hides_drafts_and_scheduled_posts() {
+    let store = site().store().await;
+
+    let published: Vec<_> = store.posts(Visibility::Published).iter().map(|p| p.slug.as_str()).collect();
+    assert_eq!(published, ["live"]);
+    assert_eq!(store.posts(Visibility::Preview).len(), 3);
+    assert!(store.get("unfinished", Visibility::Published).is_none());
+    assert!(store.get("unfinished", Visibility::Preview).is_some());
+}
+
+#[actix_web::test]
+async fn test_drafts_hidden_from_pages() {
+    let site = site();
+
+    let resp = site.get("/").await;
+    assert_eq!(resp.status, 200);
+    assert!(resp.body.contains("/post/live"));
+    assert!(!resp.body.contains("/post/unfinished"));
+    assert!(!resp.body.contains("/post/upcoming"));
+
+    let body = site.body("/archive").await;
+    assert!(!body.contains("/post/unfinished"));
+
+    assert_eq!(site.get("/post/upcoming").await.status, 404);
+}
+
+#[actix_web::test]
+async fn test_preview_mode_shows_banners() {
+    let site = site().with_config(SiteConfig { preview: true, ..SiteConfig::default() });
+
+    let body = site.body("/archive").await;
+    assert!(body.contains("/post/unfinished"));
+    assert!(body.contains("/post/upcoming"));
+
+    let resp = site.get("/post/unfinished").await;
+    assert_eq!(resp.status, 200);
+    assert!(resp.body.contains("post-banner-draft"));
+
+    let body = site.body("/post/upcoming").await;
+    assert!(body.contains("post-banner-scheduled"));
+}
+
+#[actix_web::test]
+async fn test_signed_preview_token() {
+    let config = SiteConfig {
+        preview_secret: Some("s3cret".to_string()),
+        ..SiteConfig::default()
+    };
+    let token = config.preview_token("unfinished").expect("Token with a secret");
+    assert!(config.verify_preview_token("unfinished", &token));
+    assert!(!config.verify_preview_token("upcoming", &token));
+    assert!(!config.verify_preview_token("unfinished", "not-hex"));
+
+    let site = site().with_config(config);
+
+    let resp = site.get(&format!("/post/unfinished?preview={}", token)).await;
+    assert_eq!(resp.status, 200);
+    assert!(resp.body.contains("post-banner-draft"));
+
+    // Tokens are only valid for the post they were made for
+    let resp = site.get(&format!("/post/upcoming?preview={}", token)).await;
+    assert_eq!(resp.status, 404);
+
+    // ...and listing pages stay unaffected
+    let body = site.body(&format!("/archive?preview={}", token)).await;
+    assert!(!body.contains("/post/unfinished"));
+}
+
+#[actix_web::test]
+async fn test_preview_token_unlocks_bundle_assets() {
+    let config = SiteConfig {
+        preview_secret: Some("s3cret".to_string()),
+        ..SiteConfig::default()
+    };
+    let token = config.preview_token("trip").unwrap();
+    let site = TestSite::new()
+        .with_config(config)
+        .file("trip/index.md", &common::post("trip", "2024-01-01T12:00:00Z", "draft: true\n", "\n![](view.png)\n"))
+        .file("trip/view.png", "not really a png");
+
+    assert_eq!(site.get("/post/trip/view.png").await.status, 404);
+    let resp = site.get(&format!("/post/trip/view.png?preview={}", token)).await;
+    assert_eq!(resp.status, 200);
+    assert_eq!(resp.body, "not really a png");
+}
@@ -0,0 +1,87 @@
+pub mod common;
+
+use andy::config::SiteConfig;
+use common::TestSite;
+
+// Two posts and a draft; the newer post's title and body need escaping
+fn site() -> TestSite {
+    TestSite::new()
+        .post("older", "2024-01-01T12:00:00Z", "tags: [rust]\n")
+        .file(
+            "newer.md",
+            "---\ntitle: Post <newer> & more\ndate: 2024-02-01T08:30:00Z\ndescription: About newer\nslug: newer\ntags: [web]\n---\n\nBody of **newer**\n",
+        )
+        .post("hidden", "2024-03-01T12:00:00Z", "tags: [rust]\ndraft: true\n")
+}
+
+fn config() -> SiteConfig {
+    SiteConfig {
+        base_url: "https://blog.example.com/".to_string(),
+        title: "Andy's Blog".to_string(),
+        ..SiteConfig::default()
+    }
+}
+
+#[actix_web::test]
+async fn test_rss_feed() {
+    let resp = site().with_config(config()).get("/feed.xml").await;
+    let body = &resp.body;
+
+    assert_eq!(resp.status, 200);
+    assert_eq!(resp.header("content-type"), Some("application/rss+xml; charset=utf-8"));
+    assert!(body.contains("<rss version=\"2.0\""));
+    assert!(body.contains("<title>Andy&apos;s Blog</title>"));
+    assert!(body.contains("<link>https://blog.example.com/post/newer</link>"));
+    assert!(body.contains("<title>Post &lt;newer&gt; &amp; more</title>"));
+    assert!(body.contains("<pubDate>Thu, 1 Feb 2024 08:30:00 +0000</pubDate>"));
+    assert!(body.contains("<content:encoded>&lt;p&gt;Body of &lt;strong&gt;newer"));
+    assert!(!body.contains("hidden"));
+
+    // Newest first
+    assert!(body.find("/post/newer").unwrap() < body.find("/post/older").unwrap());
+}
+
+#[actix_web::test]
+async fn test_atom_feed() {
+    let resp = site().with_config(config()).get("/atom.xml").await;
+    let body = &resp.body;
+
+    assert_eq!(resp.status, 200);
+    assert_eq!(resp.header("content-type"), Some("application/atom+xml; charset=utf-8"));
+    assert!(body.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+    assert!(body.contains("<updated>2024-02-01T08:30:00Z</updated>"));
+    assert!(body.contains("<link href=\"https://blog.example.com/atom.xml\" rel=\"self\""));
+    assert!(body.contains("<id>https://blog.example.com/post/older</id>"));
+    assert!(body.contains("<content type=\"html\""));
+}
+
+#[actix_web::test]
+async fn test_feed_description_only() {
+    let site = site().with_config(SiteConfig { feed_full_content: false, ..config() });
+    let rss = site.body("/feed.xml").await;
+    let atom = site.body("/atom.xml").await;
+
+    assert!(rss.contains("<description>About newer</description>"));
+    assert!(!rss.contains("content:encoded>"));
+    assert!(atom.contains("<summary>About newer</summary>"));
+    assert!(!atom.contains("<content"));
+}
+
+#[actix_web::test]
+async fn test_tag_feeds() {
+    let site = site().with_config(config());
+
+    let resp = site.get("/tags/rust/feed.xml").await;
+    let body = &resp.body;
+    assert_eq!(resp.status, 200);
+    assert!(body.contains("/post/older"));
+    assert!(!body.contains("/post/newer"));
+    assert!(!body.contains("/post/hidden"));
+    assert!(body.contains("https://blog.example.com/tags/rust/feed.xml"));
+
+    let resp = site.get("/tags/web/atom.xml").await;
+    assert_eq!(resp.status, 200);
+    assert!(resp.body.contains("/post/newer"));
+
+    assert_eq!(site.get("/tags/missing/feed.xml").await.status, 404);
+}
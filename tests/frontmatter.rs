@@ -0,0 +1,314 @@
+pub mod common;
+
+use andy::models::{BlogPost, BlogPostError, FrontmatterFormat};
+use common::TestSite;
+use serde_json::json;
+
+async fn parse(content: &str) -> Result<BlogPost, BlogPostError> {
+    TestSite::new().file("post.md", content).parse("post.md").await
+}
+
+#[actix_web::test]
+async fn test_yaml_values_with_colons_and_multiple_lines() {
+    let post = parse(r#"---
+title: "Rust: The Good Parts"
+date: 2024-03-01T12:00:00Z
+description: >
+  A description that is
+  folded over two lines
+slug: rust-good-parts
+---
+
+Body
+"#).await.expect("Failed to parse post");
+
+    assert_eq!(post.title, "Rust: The Good Parts");
+    assert_eq!(post.description, "A description that is folded over two lines\n");
+    assert_eq!(post.slug, "rust-good-parts");
+}
+
+#[actix_web::test]
+async fn test_unknown_keys_are_kept_in_extra() {
+    let post = parse(r#"---
+title: Extra
+date: 2024-03-01T12:00:00Z
+description: Extra fields
+slug: extra
+cover: /static/cover.png
+keywords:
+  - rust
+  - web
+social:
+  twitter: "@andy"
+---
+
+Body
+"#).await.expect("Failed to parse post");
+
+    assert_eq!(post.extra["cover"], json!("/static/cover.png"));
+    assert_eq!(post.extra["keywords"], json!(["rust", "web"]));
+    assert_eq!(post.extra["social"], json!({ "twitter": "@andy" }));
+    assert!(!post.extra.contains_key("title"));
+
+    // Templates see the extra fields on the serialized post
+    let serialized = serde_json::to_value(&post).unwrap();
+    assert_eq!(serialized["extra"]["cover"], json!("/static/cover.png"));
+}
+
+#[actix_web::test]
+async fn test_horizontal_rule_in_body_is_not_a_delimiter() {
+    let post = parse(r#"---
+title: Rules
+date: 2024-03-01T12:00:00Z
+description: Horizontal rules
+slug: rules
+---
+
+Before
+
+---
+
+After
+"#).await.expect("Failed to parse post");
+
+    assert!(post.content.contains("Before\n\n---\n\nAfter"));
+    assert!(post.html_content.contains("<hr />"));
+    assert!(post.html_content.contains("After"));
+}
+
+#[actix_web::test]
+async fn test_frontmatter_error_reports_file_line_and_column() {
+    let err = parse(r#"---
+title: Bad date
+date: yesterday
+description: Broken
+slug: bad-date
+---
+"#).await.expect_err("Invalid date should fail");
+
+    match err {
+        BlogPostError::FrontmatterParse { format, line, column, .. } => {
+            assert_eq!(format, FrontmatterFormat::Yaml);
+            assert_eq!(line, 3);
+            assert_eq!(column, 7);
+        }
+        other => panic!("Unexpected error: {}", other),
+    }
+}
+
+#[actix_web::test]
+async fn test_frontmatter_must_start_the_file() {
+    let err = parse("Intro\n\n---\ntitle: Late\n---\n")
+        .await
+        .expect_err("Frontmatter after content should fail");
+
+    assert!(matches!(err, BlogPostError::MissingFrontmatter));
+}
+
+#[actix_web::test]
+async fn test_missing_required_field() {
+    let err = parse("---\ntitle: No date\ndescription: Missing\nslug: no-date\n---\n")
+        .await
+        .expect_err("Missing date should fail");
+
+    assert!(matches!(err, BlogPostError::MissingField(field) if field == "date"));
+}
+
+#[actix_web::test]
+async fn test_toml_frontmatter() {
+    let post = parse(r#"+++
+title = "From Zola"
+date = 2024-03-01T12:00:00Z
+description = "TOML frontmatter"
+slug = "from-zola"
+
+[extra]
+cover = "cover.png"
++++
+
+Body with a rule
+
++++
+"#).await.expect("Failed to parse post");
+
+    assert_eq!(post.title, "From Zola");
+    assert_eq!(post.date.to_rfc3339(), "2024-03-01T12:00:00+00:00");
+    assert_eq!(post.slug, "from-zola");
+    assert_eq!(post.extra["extra"], json!({ "cover": "cover.png" }));
+    assert!(post.content.contains("Body with a rule"));
+}
+
+#[actix_web::test]
+async fn test_toml_bare_date() {
+    let post = parse("+++\ntitle = \"Dated\"\ndate = 2024-03-01\ndescription = \"x\"\nslug = \"dated\"\n+++\n")
+        .await
+        .expect("Failed to parse post");
+
+    assert_eq!(post.date.to_rfc3339(), "2024-03-01T00:00:00+00:00");
+}
+
+#[actix_web::test]
+async fn test_json_frontmatter() {
+    let post = parse(r#"{
+    "title": "From Hugo",
+    "date": "2024-03-01T12:00:00Z",
+    "description": "JSON frontmatter",
+    "slug": "from-hugo",
+    "weight": 3
+}
+
+# Body
+"#).await.expect("Failed to parse post");
+
+    assert_eq!(post.title, "From Hugo");
+    assert_eq!(post.extra["weight"], json!(3));
+    assert!(post.html_content.contains("<h1 id=\"body\">Body"));
+}
+
+#[actix_web::test]
+async fn test_errors_name_the_format() {
+    let toml_err = parse("+++\ntitle = \"Unclosed\n+++\n").await.expect_err("Invalid TOML should fail");
+    match toml_err {
+        BlogPostError::FrontmatterParse { format, line, .. } => {
+            assert_eq!(format, FrontmatterFormat::Toml);
+            assert_eq!(line, 2);
+        }
+        other => panic!("Unexpected error: {}", other),
+    }
+    let message = parse("+++\ntitle = 1\n+++\n").await.expect_err("Wrong type should fail").to_string();
+    assert!(message.starts_with("Failed to parse TOML frontmatter at line 2"));
+
+    let json_err = parse("{\n  \"title\": \"Trailing\",\n}\n").await.expect_err("Invalid JSON should fail");
+    match json_err {
+        BlogPostError::FrontmatterParse { format, line, .. } => {
+            assert_eq!(format, FrontmatterFormat::Json);
+            assert_eq!(line, 3);
+        }
+        other => panic!("Unexpected error: {}", other),
+    }
+}
+
+async fn parse_named(name: &str, content: &str) -> Result<BlogPost, BlogPostError> {
+    TestSite::new().file(name, content).parse(name).await
+}
+
+#[actix_web::test]
+async fn test_slug_and_date_from_filename() {
+    let post = parse_named("2024-03-01-Hello World.md", "---\ntitle: Hello\ndescription: Hi\n---\n")
+        .await
+        .expect("Failed to parse post");
+
+    assert_eq!(post.slug, "hello-world");
+    assert_eq!(post.date.to_rfc3339(), "2024-03-01T00:00:00+00:00");
+
+    // Without a date prefix only the slug comes from the name
+    let post = parse_named("plain-name.md", "---\ntitle: Plain\ndate: 2024-03-01\ndescription: Hi\n---\n")
+        .await
+        .expect("Failed to parse post");
+    assert_eq!(post.slug, "plain-name");
+}
+
+#[actix_web::test]
+async fn test_null_date_falls_back_to_filename() {
+    for date in ["date:", "date: null", "date: \"\""] {
+        let post = parse_named("2024-03-01-hello.md", &format!("---\ntitle: Hello\n{}\ndescription: Hi\n---\n", date))
+            .await
+            .expect("Failed to parse post");
+        assert_eq!(post.date.to_rfc3339(), "2024-03-01T00:00:00+00:00");
+    }
+
+    let err = parse_named("hello.md", "---\ntitle: Hello\ndate:\ndescription: Hi\n---\n")
+        .await
+        .expect_err("Null date without a prefix should fail");
+    assert!(matches!(err, BlogPostError::MissingField(field) if field == "date"));
+}
+
+#[actix_web::test]
+async fn test_frontmatter_wins_over_filename() {
+    let post = parse_named(
+        "2024-03-01-old-name.md",
+        "---\ntitle: Moved\ndate: 2024-05-01T08:00:00Z\ndescription: Hi\nslug: new-name\n---\n",
+    ).await.expect("Failed to parse post");
+
+    assert_eq!(post.slug, "new-name");
+    assert_eq!(post.date.to_rfc3339(), "2024-05-01T08:00:00+00:00");
+}
+
+#[actix_web::test]
+async fn test_date_still_required_without_prefix() {
+    let err = parse_named("2024-13-01-not-a-date.md", "---\ntitle: No date\ndescription: Hi\n---\n")
+        .await
+        .expect_err("Missing date should fail");
+
+    assert!(matches!(err, BlogPostError::MissingField(field) if field == "date"));
+}
+
+#[actix_web::test]
+async fn test_reading_time_and_excerpt() {
+    let words = vec!["word"; 450].join(" ");
+    let post = parse(&format!(
+        "---\ntitle: Long\ndate: 2024-03-01\nslug: long\n---\n\n{}\n\n```rust\nfn not_counted() {{}}\n```\n",
+        words
+    ))
+    .await
+    .expect("Failed to parse post");
+
+    assert_eq!(post.word_count, 450);
+    assert_eq!(post.reading_time_minutes, 3);
+
+    // Without a marker or description, both come from the first 50 words
+    let opening = format!("{}…", vec!["word"; 50].join(" "));
+    assert_eq!(post.description, opening);
+    assert_eq!(post.excerpt, format!("<p>{}</p>", opening));
+}
+
+#[actix_web::test]
+async fn test_excerpt_before_more_marker() {
+    let post = parse("---\ntitle: Marked\ndate: 2024-03-01\nslug: marked\n---\n\nThe *short* version & more.\n\n<!-- more -->\n\nThe rest.\n")
+        .await
+        .expect("Failed to parse post");
+
+    assert_eq!(post.excerpt, "<p>The <em>short</em> version &amp; more.</p>\n");
+    assert_eq!(post.description, "The short version & more.");
+    assert!(post.html_content.contains("The rest."));
+    assert_eq!(post.reading_time_minutes, 1);
+
+    // A description in the frontmatter is kept as is
+    let post = parse("---\ntitle: Described\ndate: 2024-03-01\nslug: described\ndescription: Hand written\n---\n\nBody text.\n")
+        .await
+        .expect("Failed to parse post");
+    assert_eq!(post.description, "Hand written");
+    assert_eq!(post.excerpt, "<p>Body text.</p>");
+}
+
+#[actix_web::test]
+async fn test_excerpt_headings_have_no_ids() {
+    let post = parse("---\ntitle: Headed\ndate: 2024-03-01\nslug: headed\n---\n\n## Intro\n\nOpening.\n\n<!-- more -->\n\nThe rest.\n")
+        .await
+        .expect("Failed to parse post");
+
+    assert_eq!(post.excerpt, "<h2>Intro</h2>\n<p>Opening.</p>\n");
+    assert!(post.html_content.contains("id=\"intro\""));
+    assert!(post.html_content.contains("heading-anchor"));
+}
+
+#[actix_web::test]
+async fn test_more_marker_in_code_is_ignored() {
+    let post = parse(
+        "---\ntitle: Code\ndate: 2024-03-01\nslug: code\n---\n\nWrite `<!-- more -->` to end the excerpt:\n\n```html\n<!-- more -->\n```\n\nSee? <!-- more --> Done.\n",
+    )
+    .await
+    .expect("Failed to parse post");
+
+    assert_eq!(post.description, "Write <!-- more --> to end the excerpt: See? Done.");
+    assert!(post.excerpt.starts_with("<p>Write &lt;!-- more --&gt; to end the excerpt:"));
+}
+
+#[actix_web::test]
+async fn test_slug_must_be_one_path_component() {
+    for slug in ["../escape", "a/b", "dots..dots"] {
+        let content = format!("---\ntitle: Bad\ndate: 2024-03-01\ndescription: Hi\nslug: \"{}\"\n---\n", slug);
+        let err = parse(&content).await.expect_err("Slug should be rejected");
+        assert!(matches!(err, BlogPostError::InvalidSlug(ref s) if s == slug), "{:?}", err);
+    }
+}
@@ -1,9 +1,9 @@
 use actix_web::{test, App, web};
-use andy::routes;
+use andy::{config::SiteConfig, routes, models::PostStore};
 use handlebars::Handlebars;
 use std::fs;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[actix_web::test]
 async fn test_home_route() {
@@ -15,7 +15,7 @@ async fn test_home_route() {
 
     // Create a test post if none exists
     let test_post_path = content_dir.join("test-post.md");
-    if !fs::metadata(&test_post_path).is_ok() {
+    if fs::metadata(&test_post_path).is_err() {
         let test_post_content = r#"---
 title: Test Post
 date: 2024-03-01T12:00:00Z
@@ -33,12 +33,18 @@ This is a test post for testing the home route.
     // Initialize handlebars with templates
     let mut handlebars = Handlebars::new();
     handlebars.register_templates_directory(".hbs", "templates").expect("Failed to register templates");
-    let handlebars_ref = Arc::new(handlebars);
+    let handlebars_ref = Arc::new(RwLock::new(handlebars));
+
+    // Load posts into the in-memory store
+    let post_store = PostStore::load("content", &SiteConfig::default()).await.expect("Failed to load blog posts");
+    let post_store_ref = Arc::new(RwLock::new(post_store));
 
     // Create test app
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(handlebars_ref))
+            .app_data(web::Data::new(post_store_ref))
+            .app_data(web::Data::new(Arc::new(SiteConfig::default())))
             .configure(routes::configure)
     ).await;
     
@@ -1,9 +1,11 @@
+pub mod common;
+
 use actix_web::{test, App, web};
-use andy::{routes, models::BlogPost};
+use andy::{config::SiteConfig, routes, models::{BlogPost, PostStore}};
 use handlebars::Handlebars;
 use tokio::fs;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[actix_web::test]
 async fn test_blog_post_creation_and_rendering() {
@@ -41,7 +43,7 @@ console.log("Hello, world!");
     fs::write(&test_post_path, test_post_content).await.unwrap();
 
     // Test loading the blog post
-    let posts = BlogPost::get_all_posts().await.expect("Failed to load blog posts");
+    let posts = BlogPost::get_all_posts(&SiteConfig::default()).await.expect("Failed to load blog posts");
     let test_post = posts.iter().find(|p| p.slug == "syntax-test").expect("Test post not found");
     
     // Verify the post has the correct content
@@ -56,12 +58,18 @@ console.log("Hello, world!");
     // Initialize handlebars with templates
     let mut handlebars = Handlebars::new();
     handlebars.register_templates_directory(".hbs", "templates").expect("Failed to register templates");
-    let handlebars_ref = Arc::new(handlebars);
+    let handlebars_ref = Arc::new(RwLock::new(handlebars));
+
+    // Load posts into the in-memory store
+    let post_store = PostStore::load("content", &SiteConfig::default()).await.expect("Failed to load blog posts");
+    let post_store_ref = Arc::new(RwLock::new(post_store));
 
     // Create test app and test accessing the post
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(handlebars_ref))
+            .app_data(web::Data::new(post_store_ref))
+            .app_data(web::Data::new(Arc::new(SiteConfig::default())))
             .configure(routes::configure)
     ).await;
     
@@ -96,54 +104,19 @@ Test post content.
 "#;
     fs::write(&test_post_path, test_post_content).await.unwrap();
 
-    // Initialize handlebars
-    let mut handlebars = Handlebars::new();
-    
-    // Register formatDate helper
-    handlebars.register_helper("formatDate", Box::new(|
-        h: &handlebars::Helper,
-        _: &handlebars::Handlebars,
-        _: &handlebars::Context,
-        _: &mut handlebars::RenderContext,
-        out: &mut dyn handlebars::Output,
-    | -> handlebars::HelperResult {
-        let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
-        
-        if let Ok(date) = chrono::DateTime::parse_from_rfc3339(param) {
-            let utc_date = date.with_timezone(&chrono::Utc);
-            let formatted = utc_date.format("%B %e, %Y").to_string();
-            out.write(&formatted)?;
-        } else {
-            out.write(param)?;
-        }
-        
-        Ok(())
-    }));
-    
-    // Register currentYear helper
-    handlebars.register_helper("currentYear", Box::new(|
-        _: &handlebars::Helper,
-        _: &handlebars::Handlebars,
-        _: &handlebars::Context,
-        _: &mut handlebars::RenderContext,
-        out: &mut dyn handlebars::Output,
-    | -> handlebars::HelperResult {
-        let current_year = chrono::Utc::now().format("%Y").to_string();
-        out.write(&current_year)?;
-        Ok(())
-    }));
-
-    // Register all templates
-    handlebars
-        .register_templates_directory(".hbs", "templates")
-        .expect("Failed to register handlebars templates");
+    // Initialize handlebars with helpers and templates
+    let handlebars_ref = Arc::new(RwLock::new(common::handlebars()));
     
-    let handlebars_ref = Arc::new(handlebars);
+    // Load posts into the in-memory store
+    let post_store = PostStore::load("content", &SiteConfig::default()).await.expect("Failed to load blog posts");
+    let post_store_ref = Arc::new(RwLock::new(post_store));
     
     // Create test app
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(handlebars_ref.clone()))
+            .app_data(web::Data::new(post_store_ref))
+            .app_data(web::Data::new(Arc::new(SiteConfig::default())))
             .service(actix_files::Files::new("/static", "static"))
             .configure(routes::configure)
     ).await;
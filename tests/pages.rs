@@ -0,0 +1,79 @@
+pub mod common;
+
+use actix_web::http::StatusCode;
+use andy::models::Visibility;
+use common::TestSite;
+
+fn site() -> TestSite {
+    TestSite::new()
+        .file("hello.md", "---\ntitle: Hello\ndate: 2024-01-01\ndescription: Hi\n---\n\nHello\n")
+        .file("pages/about.md", "---\ntitle: About Me\nmenu: true\nmenu_order: 40\n---\n\nI write *Rust*.\n\n```rust\nfn main() {}\n```\n")
+        .file("pages/contact.md", "---\ntitle: Contact\nmenu: true\nmenu_order: 5\n---\n\nMail me\n")
+        .file("pages/colophon.md", "---\ntitle: Colophon\n---\n\nMade with andy\n")
+}
+
+#[actix_web::test]
+async fn test_pages_are_not_posts() {
+    let store = TestSite::new().file("pages/about.md", "---\ntitle: About\n---\n\nHi\n").store().await;
+
+    assert!(store.is_empty());
+    assert_eq!(store.pages().len(), 1);
+    assert_eq!(store.page("about").map(|p| p.title.as_str()), Some("About"));
+    assert!(store.get("about", Visibility::Preview).is_none());
+}
+
+#[actix_web::test]
+async fn test_page_is_rendered_from_markdown() {
+    let site = site();
+
+    let body = site.body("/about").await;
+    assert!(body.contains("<h1>About Me</h1>"));
+    assert!(body.contains("<em>Rust</em>"));
+    assert!(body.contains(r#"<pre class="code-block code-rust">"#));
+
+    // Pages without a menu entry are still served
+    assert_eq!(site.get("/colophon").await.status, StatusCode::OK);
+
+    assert_eq!(site.get("/missing").await.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_nav_is_built_from_config_and_pages() {
+    let site = site();
+
+    let body = site.body("/archive").await;
+
+    let links = ["/contact", "/\"", "/archive", "/tags", "/about"];
+    let positions: Vec<_> = links
+        .iter()
+        .map(|link| body.find(&format!("<li><a href=\"{}", link)).unwrap_or_else(|| panic!("missing {}", link)))
+        .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "{:?}", positions);
+    assert!(!body.contains("href=\"/colophon\""));
+}
+
+#[actix_web::test]
+async fn test_page_bundle_assets() {
+    let site = TestSite::new()
+        .file("pages/team/index.md", "---\ntitle: Team\n---\n\n![Us](photo.jpg)\n")
+        .file("pages/team/photo.jpg", "not really a jpeg")
+        .file("pages/team/notes.md", "Not a page");
+
+    let store = site.store().await;
+    let team = store.page("team").unwrap();
+    assert!(team.html_content.contains(r#"<img src="/team/photo.jpg" alt="Us" />"#));
+    assert_eq!(store.pages().len(), 1);
+
+    let resp = site.get("/team/photo.jpg").await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.body, "not really a jpeg");
+    for uri in ["/team/index.md", "/team/notes.md", "/team/missing.jpg", "/about/photo.jpg"] {
+        assert_eq!(site.get(uri).await.status, StatusCode::NOT_FOUND, "{}", uri);
+    }
+
+    let out = tempfile::tempdir().unwrap();
+    site.build(out.path()).await;
+    assert!(out.path().join("team/index.html").exists());
+    assert_eq!(std::fs::read_to_string(out.path().join("team/photo.jpg")).unwrap(), "not really a jpeg");
+    assert!(!out.path().join("team/notes.md").exists());
+}
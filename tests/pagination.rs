@@ -0,0 +1,63 @@
+pub mod common;
+
+use actix_web::http::StatusCode;
+use andy::config::SiteConfig;
+use common::TestSite;
+
+// Five posts, two per page on the home page and three in the archive
+fn site() -> TestSite {
+    let mut site = TestSite::new().with_config(SiteConfig {
+        home_page_size: 2,
+        archive_page_size: 3,
+        ..SiteConfig::default()
+    });
+    for day in 1..=5 {
+        site = site.post(&format!("post-{day}"), &format!("2024-01-0{day}T12:00:00Z"), "");
+    }
+    site
+}
+
+#[actix_web::test]
+async fn test_home_pages() {
+    let site = site();
+
+    let body = site.body("/").await;
+    assert!(body.contains("/post/post-5") && body.contains("/post/post-4"));
+    assert!(!body.contains("/post/post-3"));
+    assert!(body.contains("Page 1 of 3"));
+    assert!(body.contains(r#"href="/page/2""#));
+    assert!(!body.contains(r#"rel="prev""#));
+
+    let body = site.body("/page/3").await;
+    assert!(body.contains("/post/post-1"));
+    assert!(!body.contains("/post/post-2"));
+    assert!(body.contains(r#"href="/page/2""#));
+    assert!(!body.contains(r#"rel="next""#));
+
+    // Page 2 links back to the home page itself
+    let body = site.body("/page/2").await;
+    assert!(body.contains(r#"<a href="/" class="pagination-prev""#));
+}
+
+#[actix_web::test]
+async fn test_archive_pages() {
+    let site = site();
+
+    let body = site.body("/archive").await;
+    assert!(body.contains("/post/post-3"));
+    assert!(!body.contains("/post/post-2"));
+    assert!(body.contains(r#"href="/archive/page/2""#));
+
+    let body = site.body("/archive/page/2").await;
+    assert!(body.contains("/post/post-2") && body.contains("/post/post-1"));
+    assert!(body.contains(r#"href="/archive""#));
+}
+
+#[actix_web::test]
+async fn test_out_of_range_pages_are_not_found() {
+    let site = site();
+
+    for uri in ["/page/0", "/page/4", "/page/abc", "/archive/page/0", "/archive/page/3"] {
+        assert_eq!(site.get(uri).await.status, StatusCode::NOT_FOUND, "{}", uri);
+    }
+}
@@ -0,0 +1,164 @@
+pub mod common;
+
+use andy::models::{BlogPost, Visibility};
+use common::TestSite;
+
+#[actix_web::test]
+async fn test_store_loads_posts_newest_first() {
+    let store = TestSite::new()
+        .post("older", "2024-01-01T12:00:00Z", "")
+        .post("newer", "2024-02-01T12:00:00Z", "")
+        .file("notes.txt", "not a post")
+        .store()
+        .await;
+
+    assert_eq!(store.len(), 2);
+    let slugs: Vec<_> = store.all().iter().map(|p| p.slug.as_str()).collect();
+    assert_eq!(slugs, ["newer", "older"]);
+    assert_eq!(store.latest(Visibility::Published, 1).len(), 1);
+    assert_eq!(store.latest(Visibility::Published, 10).len(), 2);
+}
+
+#[actix_web::test]
+async fn test_store_lookup_by_slug() {
+    let store = TestSite::new().post("first", "2024-01-01T12:00:00Z", "").store().await;
+
+    assert_eq!(store.get("first", Visibility::Published).map(|p| p.title.as_str()), Some("Post first"));
+    assert!(store.get("missing", Visibility::Published).is_none());
+}
+
+#[actix_web::test]
+async fn test_store_insert_replaces_post_from_same_file() {
+    let site = TestSite::new()
+        .file("post.md", &common::post("original", "2024-01-01T12:00:00Z", "", ""))
+        .post("other", "2024-03-01T12:00:00Z", "");
+    let mut store = site.store().await;
+
+    // Change the slug and date of an existing file
+    common::write(&site.path().join("post.md"), &common::post("renamed", "2024-06-01T12:00:00Z", "", ""));
+    store.insert(site.parse("post.md").await.unwrap());
+
+    assert_eq!(store.len(), 2);
+    assert!(store.get("original", Visibility::Published).is_none());
+    assert_eq!(store.all()[0].slug, "renamed");
+}
+
+#[actix_web::test]
+async fn test_store_remove_by_source() {
+    let site = TestSite::new().file("post.md", &common::post("doomed", "2024-01-01T12:00:00Z", "", ""));
+    let mut store = site.store().await;
+
+    let removed = store.remove(&site.path().join("post.md"));
+
+    assert_eq!(removed.map(|p| p.slug), Some("doomed".to_string()));
+    assert!(store.is_empty());
+    assert!(store.get("doomed", Visibility::Published).is_none());
+}
+
+#[actix_web::test]
+async fn test_store_suffixes_duplicate_slugs() {
+    let site = TestSite::new()
+        .file("original.md", &common::post("hello", "2024-01-01T12:00:00Z", "", ""))
+        .file("copy.md", &common::post("hello", "2024-02-01T12:00:00Z", "", ""))
+        .file("another-copy.md", &common::post("hello", "2024-03-01T12:00:00Z", "", ""))
+        // Already taken, so the first copy can't use it
+        .file("real.md", &common::post("hello-2", "2023-01-01T12:00:00Z", "", ""));
+
+    let store = site.store().await;
+
+    // The oldest post keeps its slug no matter which file is found first
+    let source = |slug| store.get(slug, Visibility::Published).map(|p| p.source.clone());
+    assert_eq!(source("hello"), Some(site.path().join("original.md")));
+    assert_eq!(source("hello-2"), Some(site.path().join("real.md")));
+    assert_eq!(source("hello-3"), Some(site.path().join("copy.md")));
+    assert_eq!(source("hello-4"), Some(site.path().join("another-copy.md")));
+
+    let duplicates = store.duplicates();
+    assert_eq!(duplicates.len(), 2);
+    assert_eq!(duplicates[0].slug, "hello");
+    assert_eq!(duplicates[0].kept, site.path().join("original.md"));
+    assert_eq!(duplicates[0].renamed, site.path().join("copy.md"));
+    assert_eq!(duplicates[0].new_slug, "hello-3");
+}
+
+#[actix_web::test]
+async fn test_store_duplicate_slugs_after_reload() {
+    let site = TestSite::new().file("original.md", &common::post("hello", "2024-01-01T12:00:00Z", "", ""));
+    let mut store = site.store().await;
+
+    // A newer post claiming the slug doesn't take it over
+    common::write(&site.path().join("copy.md"), &common::post("hello", "2024-02-01T12:00:00Z", "", ""));
+    store.insert(site.parse("copy.md").await.unwrap());
+    assert_eq!(store.get("hello", Visibility::Published).unwrap().source, site.path().join("original.md"));
+    assert_eq!(store.get("hello-2", Visibility::Published).unwrap().source, site.path().join("copy.md"));
+
+    // Once the original is gone, the copy gets the slug back
+    store.remove(&site.path().join("original.md"));
+    assert_eq!(store.get("hello", Visibility::Published).unwrap().source, site.path().join("copy.md"));
+    assert!(store.get("hello-2", Visibility::Published).is_none());
+    assert!(store.duplicates().is_empty());
+}
+
+#[actix_web::test]
+async fn test_previous_and_next_posts() {
+    let store = TestSite::new()
+        .post("one", "2024-01-01T12:00:00Z", "")
+        .post("two", "2024-02-01T12:00:00Z", "")
+        .post("three", "2024-03-01T12:00:00Z", "")
+        .post("draft", "2024-02-15T12:00:00Z", "draft: true\n")
+        .store()
+        .await;
+    let slugs = |(previous, next): (Option<&BlogPost>, Option<&BlogPost>)| {
+        (previous.map(|p| p.slug.clone()), next.map(|p| p.slug.clone()))
+    };
+    let post = |slug| store.get(slug, Visibility::Preview).unwrap();
+
+    assert_eq!(slugs(store.neighbours(post("one"), Visibility::Published)), (None, Some("two".into())));
+    assert_eq!(slugs(store.neighbours(post("three"), Visibility::Published)), (Some("two".into()), None));
+
+    // The draft is skipped unless previewing
+    assert_eq!(
+        slugs(store.neighbours(post("two"), Visibility::Published)),
+        (Some("one".into()), Some("three".into()))
+    );
+    assert_eq!(
+        slugs(store.neighbours(post("two"), Visibility::Preview)),
+        (Some("one".into()), Some("draft".into()))
+    );
+
+    // A previewed draft still gets published neighbours
+    assert_eq!(
+        slugs(store.neighbours(post("draft"), Visibility::Published)),
+        (Some("two".into()), Some("three".into()))
+    );
+}
+
+#[actix_web::test]
+async fn test_related_posts() {
+    // Titles matter here, so the posts are written out in full
+    let mut site = TestSite::new();
+    for (slug, date, title, tags) in [
+        ("post", "2024-01-01T12:00:00Z", "Async Rust in Practice", "rust, async"),
+        ("both-tags", "2024-01-02T12:00:00Z", "Something Else", "Rust, async"),
+        ("one-tag", "2024-01-03T12:00:00Z", "Gardening", "rust"),
+        ("similar-title", "2024-01-04T12:00:00Z", "Practice Makes Async Perfect", "cooking"),
+        ("unrelated", "2024-01-05T12:00:00Z", "Holiday Photos", "travel"),
+    ] {
+        site = site.file(
+            &format!("{slug}.md"),
+            &format!("---\ntitle: {title}\ndate: {date}\ndescription: {slug}\nslug: {slug}\ntags: [{tags}]\n---\n"),
+        );
+    }
+
+    let store = site.store().await;
+    let post = store.get("post", Visibility::Published).unwrap();
+
+    let related: Vec<_> = store
+        .related(post, Visibility::Published, 10)
+        .iter()
+        .map(|p| p.slug.as_str())
+        .collect();
+    assert_eq!(related, ["both-tags", "one-tag", "similar-title"]);
+
+    assert_eq!(store.related(post, Visibility::Published, 1).len(), 1);
+}
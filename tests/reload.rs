@@ -0,0 +1,90 @@
+pub mod common;
+
+use andy::{models::Visibility, reload};
+use common::TestSite;
+use handlebars::Handlebars;
+use serde_json::json;
+use std::fs;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+const DATE: &str = "2024-03-01T12:00:00Z";
+
+// Polls `check` until it passes, giving the watcher time to pick up changes
+async fn eventually(mut check: impl FnMut() -> bool) -> bool {
+    for _ in 0..50 {
+        if check() {
+            return true;
+        }
+        actix_web::rt::time::sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[actix_web::test]
+async fn test_watcher_reloads_changed_posts_and_templates() {
+    let site = TestSite::new().file("existing.md", &common::post("existing", DATE, "", "\nBefore\n"));
+    let templates_dir = tempfile::tempdir().unwrap();
+    fs::write(templates_dir.path().join("greeting.hbs"), "Hello {{name}}").unwrap();
+
+    let store = Arc::new(RwLock::new(site.store().await));
+    let mut handlebars = Handlebars::new();
+    handlebars.register_templates_directory(".hbs", templates_dir.path()).unwrap();
+    let handlebars = Arc::new(RwLock::new(handlebars));
+
+    let _watcher = reload::watch(
+        site.path(),
+        templates_dir.path(),
+        store.clone(),
+        handlebars.clone(),
+        Arc::new(site.config.clone()),
+    )
+    .expect("Failed to start watcher");
+
+    // Edit a post, add a new one and change a template
+    common::write(&site.path().join("existing.md"), &common::post("existing", DATE, "", "\nAfter\n"));
+    common::write_post(site.path(), "added", DATE, "");
+    fs::write(templates_dir.path().join("greeting.hbs"), "Goodbye {{name}}").unwrap();
+
+    assert!(eventually(|| {
+        let store = store.read().unwrap();
+        store.len() == 2 && store.get("existing", Visibility::Published).is_some_and(|p| p.html_content.contains("After"))
+    }).await);
+    assert!(eventually(|| {
+        let rendered = handlebars.read().unwrap().render("greeting", &json!({"name": "Andy"}));
+        rendered.is_ok_and(|html| html == "Goodbye Andy")
+    }).await);
+
+    // A broken post is logged and the previous version is kept
+    fs::write(site.path().join("added.md"), "no frontmatter here").unwrap();
+    fs::remove_file(site.path().join("existing.md")).unwrap();
+
+    assert!(eventually(|| store.read().unwrap().get("existing", Visibility::Published).is_none()).await);
+    assert!(store.read().unwrap().get("added", Visibility::Published).is_some());
+}
+
+#[actix_web::test]
+async fn test_watcher_ignores_what_loading_skips() {
+    let site = TestSite::new().file("trip/index.md", "---\ntitle: Trip\ndate: 2024-03-01T12:00:00Z\ndescription: Photos\n---\n\nBody\n");
+    let templates_dir = tempfile::tempdir().unwrap();
+
+    let store = Arc::new(RwLock::new(site.store().await));
+    let _watcher = reload::watch(
+        site.path(),
+        templates_dir.path(),
+        store.clone(),
+        Arc::new(RwLock::new(Handlebars::new())),
+        Arc::new(site.config.clone()),
+    )
+    .expect("Failed to start watcher");
+
+    // Notes in a bundle and editor lock files are not posts
+    common::write_post(&site.path().join("trip"), "notes", DATE, "");
+    common::write_post(site.path(), ".#draft", DATE, "");
+    common::write_post(site.path(), "real", DATE, "");
+
+    assert!(eventually(|| store.read().unwrap().get("real", Visibility::Published).is_some()).await);
+    let store = store.read().unwrap();
+    let slugs: Vec<_> = store.all().iter().map(|p| p.slug.as_str()).collect();
+    assert_eq!(slugs, ["real", "trip"]);
+}
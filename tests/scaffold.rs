@@ -0,0 +1,47 @@
+pub mod common;
+
+use andy::scaffold::{self, ScaffoldError};
+use chrono::{TimeZone, Utc};
+use common::TestSite;
+use std::fs;
+
+#[actix_web::test]
+async fn test_new_post_is_valid() {
+    let site = TestSite::new();
+    let now = Utc.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap();
+
+    let path = scaffold::new_post(&site.config, "Rust: \"a\" tour", now).await.expect("Failed to create post");
+    assert_eq!(path, site.path().join("rust-a-tour.md"));
+
+    let post = site.parse("rust-a-tour.md").await.expect("Scaffolded post should parse");
+    assert_eq!(post.title, "Rust: \"a\" tour");
+    assert_eq!(post.slug, "rust-a-tour");
+    assert_eq!(post.date, now);
+    assert_eq!(post.description, "");
+}
+
+#[actix_web::test]
+async fn test_new_post_never_overwrites() {
+    let site = TestSite::new().file("hello.md", "my draft");
+
+    let result = scaffold::new_post(&site.config, "Hello", Utc::now()).await;
+    assert!(matches!(result, Err(ScaffoldError::FileExists(_))));
+    assert_eq!(fs::read_to_string(site.path().join("hello.md")).unwrap(), "my draft");
+}
+
+#[actix_web::test]
+async fn test_new_post_rejects_taken_slug() {
+    let site = TestSite::new().file("old-copy.md", &common::post("hello", "2024-01-01T00:00:00Z", "", "\nOld\n"));
+
+    match scaffold::new_post(&site.config, "Hello", Utc::now()).await {
+        Err(ScaffoldError::SlugTaken { slug, path }) => {
+            assert_eq!(slug, "hello");
+            assert_eq!(path, site.path().join("old-copy.md"));
+        }
+        other => panic!("Expected SlugTaken, got {:?}", other),
+    }
+    assert!(!site.path().join("hello.md").exists());
+
+    let result = scaffold::new_post(&site.config, "???", Utc::now()).await;
+    assert!(matches!(result, Err(ScaffoldError::EmptySlug(_))));
+}
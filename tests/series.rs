@@ -0,0 +1,104 @@
+pub mod common;
+
+use actix_web::http::StatusCode;
+use andy::models::{BlogPostError, Visibility};
+use common::TestSite;
+
+// Parts published out of order, plus a post outside the series
+fn site() -> TestSite {
+    TestSite::new()
+        .post("intro", "2024-03-01T12:00:00Z", "series: Async Rust\nseries_order: 1\n")
+        .post("futures", "2024-01-01T12:00:00Z", "series: Async Rust\nseries_order: 2\n")
+        .post("runtimes", "2024-02-01T12:00:00Z", "series: async rust\nseries_order: 3\n")
+        .post("standalone", "2024-02-15T12:00:00Z", "")
+}
+
+#[actix_web::test]
+async fn test_series_parts_in_order() {
+    let store = site().store().await;
+
+    let all = store.all_series(Visibility::Published);
+    assert_eq!(all.len(), 1);
+
+    let series = store.series("async-rust", Visibility::Published).expect("series should exist");
+    assert_eq!(series.name, "Async Rust");
+    let slugs: Vec<_> = series.posts.iter().map(|post| post.slug.as_str()).collect();
+    assert_eq!(slugs, ["intro", "futures", "runtimes"]);
+
+    let futures = store.get("futures", Visibility::Published).unwrap();
+    assert_eq!(series.part(futures), Some(2));
+    assert!(store.series_warnings().is_empty());
+}
+
+#[actix_web::test]
+async fn test_series_page() {
+    let site = site();
+
+    let resp = site.get("/series/async-rust").await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.body;
+    assert!(body.contains("Series: Async Rust"));
+    assert!(body.contains("3 parts"));
+    let intro = body.find("/post/intro").unwrap();
+    let futures = body.find("/post/futures").unwrap();
+    let runtimes = body.find("/post/runtimes").unwrap();
+    assert!(intro < futures && futures < runtimes);
+    assert!(!body.contains("/post/standalone"));
+
+    assert_eq!(site.get("/series/missing").await.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_post_shows_its_place_in_the_series() {
+    let site = site();
+
+    let body = site.body("/post/futures").await;
+    assert!(body.contains("Part 2 of 3 in"));
+    assert!(body.contains("<a href=\"/series/async-rust\">Async Rust</a>"));
+    assert!(body.contains("<li><a href=\"/post/intro\">Post intro</a></li>"));
+    assert!(body.contains("<li class=\"series-current\">Post futures</li>"));
+    assert!(body.contains("<li><a href=\"/post/runtimes\">Post runtimes</a></li>"));
+
+    let body = site.body("/post/standalone").await;
+    assert!(!body.contains("series-box"));
+}
+
+#[actix_web::test]
+async fn test_series_gaps_and_duplicate_parts() {
+    let site = TestSite::new()
+        .post("one", "2024-01-01T12:00:00Z", "series: Guide\nseries_order: 1\n")
+        .post("also-one", "2024-01-02T12:00:00Z", "series: Guide\nseries_order: 1\n")
+        .post("four", "2024-01-04T12:00:00Z", "series: Guide\nseries_order: 4\n");
+
+    let store = site.store().await;
+    let messages: Vec<_> = store
+        .series_warnings()
+        .iter()
+        .map(|warning| (warning.source.file_name().unwrap().to_str().unwrap(), warning.message.as_str()))
+        .collect();
+    assert_eq!(messages.len(), 2, "{:#?}", messages);
+    assert_eq!(messages[0].0, "also-one.md");
+    assert!(messages[0].1.starts_with("Part 1 of series \"Guide\" is also claimed by"));
+    assert_eq!(messages[1], ("four.md", "Series \"Guide\" has no parts 2–3"));
+
+    // The problems are reported by `check` too
+    let problems = site.check().await;
+    assert_eq!(problems.len(), 2);
+
+    // A single missing part is named on its own
+    let site = TestSite::new()
+        .post("one", "2024-01-01T12:00:00Z", "series: Guide\nseries_order: 1\n")
+        .post("three", "2024-01-03T12:00:00Z", "series: Guide\nseries_order: 3\n");
+    let warnings = site.store().await.series_warnings().to_vec();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message, "Series \"Guide\" has no part 2");
+}
+
+#[actix_web::test]
+async fn test_series_order_out_of_range() {
+    for order in [0, 3000000] {
+        let site = TestSite::new().post("huge", "2024-01-01T12:00:00Z", &format!("series: Guide\nseries_order: {order}\n"));
+        let err = site.parse("huge.md").await.expect_err("series_order should be rejected");
+        assert!(matches!(err, BlogPostError::InvalidSeriesOrder(n) if n == order));
+    }
+}
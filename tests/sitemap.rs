@@ -0,0 +1,54 @@
+pub mod common;
+
+use andy::config::SiteConfig;
+use common::TestSite;
+
+fn site() -> TestSite {
+    TestSite::new()
+        .post("first", "2024-01-01T12:00:00Z", "tags: [rust]\n")
+        .post("second", "2024-02-01T12:00:00Z", "tags: [rust, web]\ncategories: [notes]\n")
+        .post("draft", "2024-03-01T12:00:00Z", "tags: [secret]\ndraft: true\n")
+        .post("future", "2999-01-01T12:00:00Z", "")
+        .file("pages/about.md", "---\ntitle: About\n---\n\nHi\n")
+}
+
+fn config() -> SiteConfig {
+    SiteConfig {
+        base_url: "https://blog.example.com".to_string(),
+        ..SiteConfig::default()
+    }
+}
+
+#[actix_web::test]
+async fn test_sitemap_lists_pages_and_posts() {
+    let body = site().with_config(config()).body("/sitemap.xml").await;
+
+    for path in ["/", "/about", "/archive", "/post/first", "/post/second", "/tags", "/tags/rust", "/tags/web", "/categories/notes"] {
+        let loc = format!("<loc>https://blog.example.com{}</loc>", path);
+        assert!(body.contains(&loc), "missing {}", loc);
+    }
+    assert!(body.contains("<loc>https://blog.example.com/post/first</loc>\n<lastmod>2024-01-01T12:00:00Z</lastmod>"));
+    assert!(body.contains("<loc>https://blog.example.com/tags/rust</loc>\n<lastmod>2024-02-01T12:00:00Z</lastmod>"));
+}
+
+#[actix_web::test]
+async fn test_sitemap_excludes_drafts_and_scheduled_posts() {
+    // Even in preview mode
+    let site = site().with_config(SiteConfig { preview: true, ..config() });
+    let body = site.body("/sitemap.xml").await;
+
+    assert!(!body.contains("/post/draft"));
+    assert!(!body.contains("/post/future"));
+    assert!(!body.contains("/tags/secret"));
+}
+
+#[actix_web::test]
+async fn test_robots_txt() {
+    let body = site().with_config(config()).body("/robots.txt").await;
+    assert!(body.contains("User-agent: *\nDisallow: /api/\n"));
+    assert!(body.contains("Sitemap: https://blog.example.com/sitemap.xml"));
+
+    let site = site().with_config(SiteConfig { robots_disallow: vec![], ..config() });
+    let body = site.body("/robots.txt").await;
+    assert!(body.contains("User-agent: *\nDisallow:\n"));
+}
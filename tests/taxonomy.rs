@@ -0,0 +1,63 @@
+pub mod common;
+
+use andy::{models::{Taxonomy, Visibility}, slug::slugify};
+use common::TestSite;
+
+fn site() -> TestSite {
+    TestSite::new()
+        .post("first", "2024-01-01T12:00:00Z", "tags: [Rust, Web Dev]\ncategories: [Programming]\n")
+        .post("second", "2024-02-01T12:00:00Z", "tags: [rust]\ncategories: [Programming]\n")
+        .post("third", "2024-03-01T12:00:00Z", "tags: []\ncategories: [Life]\n")
+}
+
+#[actix_web::test]
+async fn test_slugify() {
+    assert_eq!(slugify("Web Dev"), "web-dev");
+    assert_eq!(slugify("  Rust & C++ "), "rust-c");
+    assert_eq!(slugify("Ünïcode Tag"), "ünïcode-tag");
+}
+
+#[actix_web::test]
+async fn test_store_terms_and_counts() {
+    let store = site().store().await;
+
+    let tags = store.terms(Taxonomy::Tags, Visibility::Published);
+    let tags: Vec<_> = tags.iter().map(|t| (t.slug.as_str(), t.count)).collect();
+    assert_eq!(tags, [("rust", 2), ("web-dev", 1)]);
+
+    // Terms match by slug and use the spelling from the newest post
+    let (term, posts) = store.term(Taxonomy::Tags, "rust", Visibility::Published).expect("Tag not found");
+    assert_eq!(term.name, "rust");
+    let slugs: Vec<_> = posts.iter().map(|p| p.slug.as_str()).collect();
+    assert_eq!(slugs, ["second", "first"]);
+
+    assert_eq!(store.terms(Taxonomy::Categories, Visibility::Published).len(), 2);
+    assert!(store.term(Taxonomy::Tags, "missing", Visibility::Published).is_none());
+}
+
+#[actix_web::test]
+async fn test_taxonomy_routes() {
+    let site = site();
+
+    let body = site.body("/tags").await;
+    assert!(body.contains("href=\"/tags/web-dev\""));
+    assert!(body.contains("(2)"));
+
+    let body = site.body("/tags/web-dev").await;
+    assert!(body.contains("Tag: Web Dev"));
+    assert!(body.contains("/post/first"));
+    assert!(!body.contains("/post/second"));
+
+    let body = site.body("/categories/life").await;
+    assert!(body.contains("/post/third"));
+
+    assert_eq!(site.get("/tags/unknown").await.status, 404);
+
+    // Posts and home page cards link to their tags
+    let body = site.body("/post/first").await;
+    assert!(body.contains("href=\"/tags/web-dev\""));
+    assert!(body.contains("href=\"/categories/programming\""));
+
+    let body = site.body("/").await;
+    assert!(body.contains("href=\"/tags/rust\""));
+}
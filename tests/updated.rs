@@ -0,0 +1,97 @@
+pub mod common;
+
+use andy::config::SiteConfig;
+use chrono::{TimeZone, Utc};
+use common::TestSite;
+
+fn site() -> TestSite {
+    TestSite::new()
+        .post("revised", "2024-01-01T12:00:00Z", "updated: 2024-06-15T09:00:00Z\n")
+        .post("untouched", "2024-01-01T12:00:00Z", "")
+        // An `updated` date before `date` never moves the post back in time
+        .post("backdated", "2024-03-01T12:00:00Z", "updated: 2024-02-01T12:00:00Z\n")
+}
+
+#[actix_web::test]
+async fn test_updated_from_frontmatter() {
+    let site = TestSite::new().post("revised", "2024-01-01T12:00:00Z", "updated: 2024-06-15\n");
+    let post = site.parse("revised.md").await.expect("Failed to parse post");
+
+    assert_eq!(post.updated, Some(Utc.with_ymd_and_hms(2024, 6, 15, 0, 0, 0).unwrap()));
+    assert_eq!(post.last_modified(), Utc.with_ymd_and_hms(2024, 6, 15, 0, 0, 0).unwrap());
+}
+
+#[actix_web::test]
+async fn test_blank_updated_is_none() {
+    let site = TestSite::new()
+        .post("blank", "2024-01-01T12:00:00Z", "updated:\n")
+        .post("quoted", "2024-01-01T12:00:00Z", "updated: \"\"\n")
+        .post("null", "2024-01-01T12:00:00Z", "updated: null\n");
+
+    for name in ["blank.md", "quoted.md", "null.md"] {
+        let post = site.parse(name).await.expect("Failed to parse post");
+        assert_eq!(post.updated, None);
+        assert_eq!(post.last_modified(), Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap());
+    }
+}
+
+#[actix_web::test]
+async fn test_updated_from_mtime() {
+    let site = TestSite::new().post("edited", "2024-01-01T12:00:00Z", "");
+
+    // Off by default
+    let post = site.parse("edited.md").await.expect("Failed to parse post");
+    assert_eq!(post.updated, None);
+    assert_eq!(post.last_modified(), post.date);
+
+    // The file was just written, long after the post's date
+    let site = site.with_config(SiteConfig { updated_from_mtime: true, ..SiteConfig::default() });
+    let post = site.parse("edited.md").await.expect("Failed to parse post");
+    let updated = post.updated.expect("mtime should be used");
+    assert!(Utc::now() - updated < chrono::Duration::minutes(5));
+
+    // An explicit date always wins
+    common::write_post(site.path(), "edited", "2024-01-01T12:00:00Z", "updated: 2024-02-02T00:00:00Z\n");
+    let post = site.parse("edited.md").await.expect("Failed to parse post");
+    assert_eq!(post.updated, Some(Utc.with_ymd_and_hms(2024, 2, 2, 0, 0, 0).unwrap()));
+}
+
+#[actix_web::test]
+async fn test_post_page_shows_updated_date() {
+    let site = site();
+
+    let resp = site.get("/post/revised").await;
+    assert_eq!(resp.status, 200);
+    assert!(resp.body.contains("Updated on <time datetime=\"2024-06-15T09:00:00Z\">June 15, 2024</time>"));
+    assert_eq!(resp.header("last-modified"), Some("Sat, 15 Jun 2024 09:00:00 GMT"));
+
+    let resp = site.get("/post/untouched").await;
+    assert_eq!(resp.status, 200);
+    assert!(!resp.body.contains("Updated on"));
+    assert_eq!(resp.header("last-modified"), Some("Mon, 01 Jan 2024 12:00:00 GMT"));
+}
+
+#[actix_web::test]
+async fn test_sitemap_and_feeds_use_updated_date() {
+    let site = site();
+
+    let sitemap = site.body("/sitemap.xml").await;
+    assert!(sitemap.contains("<loc>http://localhost:8080/post/revised</loc>\n<lastmod>2024-06-15T09:00:00Z</lastmod>"));
+    assert!(sitemap.contains("<loc>http://localhost:8080/post/untouched</loc>\n<lastmod>2024-01-01T12:00:00Z</lastmod>"));
+
+    let atom = site.body("/atom.xml").await;
+    assert!(atom.contains("<published>2024-01-01T12:00:00Z</published>\n<updated>2024-06-15T09:00:00Z</updated>"));
+    // The feed itself changed when its latest post did
+    assert!(atom.contains("<id>http://localhost:8080/atom.xml</id>\n<updated>2024-06-15T09:00:00Z</updated>"));
+
+    let rss = site.body("/feed.xml").await;
+    assert!(rss.contains("<lastBuildDate>Sat, 15 Jun 2024 09:00:00 +0000</lastBuildDate>"));
+
+    let feed = site.get("/feed.json").await.json();
+    let revised = feed["items"].as_array().unwrap().iter().find(|item| item["title"] == "Post revised").unwrap();
+    assert_eq!(revised["date_modified"], "2024-06-15T09:00:00Z");
+    let untouched = feed["items"].as_array().unwrap().iter().find(|item| item["title"] == "Post untouched").unwrap();
+    assert!(untouched.get("date_modified").is_none());
+    let backdated = feed["items"].as_array().unwrap().iter().find(|item| item["title"] == "Post backdated").unwrap();
+    assert_eq!(backdated["date_modified"], "2024-03-01T12:00:00Z");
+}